@@ -1,18 +1,66 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	AccountId, BabeConfig, BalancesConfig, GenesisConfig, GrandpaConfig, SessionConfig,
+	SudoConfig, SystemConfig, CouncilConfig, DemocracyConfig, VestingConfig, ContractsConfig,
+	ValidatorSetConfig, PoeModuleConfig, WASM_BINARY, Signature, Balance, BlockNumber, DAYS,
+	SS58_PREFIX, TOKEN_SYMBOL, TOKEN_DECIMALS, opaque::SessionKeys,
 };
-use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_consensus_babe::AuthorityId as BabeId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{Verify, IdentifyAccount};
-use sc_service::ChainType;
+use sc_service::{ChainType, Properties};
+use sc_chain_spec::ChainSpecExtension;
+use serde::{Serialize, Deserialize};
 
-// The URL for the telemetry server.
-// const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+/// GRANDPA parameters shared by every node of a chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FinalityParams {
+	/// Interval between GRANDPA gossip rounds, in milliseconds.
+	pub gossip_duration_millis: u64,
+	/// Number of blocks between justifications persisted to the database.
+	pub justification_period: u32,
+	/// Whether non-authorities run the GRANDPA observer instead of the full voter. Off by
+	/// default, since the voters also relay votes for the authorities.
+	pub observer_enabled: bool,
+}
+
+impl Default for FinalityParams {
+	fn default() -> Self {
+		FinalityParams {
+			gossip_duration_millis: 333,
+			justification_period: 512,
+			observer_enabled: false,
+		}
+	}
+}
+
+/// Node `ChainSpec` extensions.
+///
+/// Additional parameters for some Substrate core modules,
+/// customizable from the chain spec.
+#[derive(Default, Clone, Serialize, Deserialize, ChainSpecExtension)]
+#[serde(rename_all = "camelCase")]
+pub struct Extensions {
+	/// Parameters of the GRANDPA finality gadget.
+	#[serde(default)]
+	pub finality: FinalityParams,
+}
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
-pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
+pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig, Extensions>;
+
+/// Token and address format properties shown by wallets.
+pub fn properties() -> Properties {
+	let mut properties = Properties::new();
+	properties.insert("tokenSymbol".into(), TOKEN_SYMBOL.into());
+	properties.insert("tokenDecimals".into(), TOKEN_DECIMALS.into());
+	properties.insert("ss58Format".into(), SS58_PREFIX.into());
+	properties
+}
+
+/// Initial balance of the pre-funded development accounts.
+const ENDOWMENT: Balance = 1 << 60;
 
 /// Generate a crypto pair from seed.
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
@@ -30,14 +78,20 @@ pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
+/// Generate a validator account together with its BABE and Grandpa session keys.
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, BabeId, GrandpaId) {
 	(
-		get_from_seed::<AuraId>(s),
+		get_account_id_from_seed::<sr25519::Public>(s),
+		get_from_seed::<BabeId>(s),
 		get_from_seed::<GrandpaId>(s),
 	)
 }
 
+/// Bundle the BABE and Grandpa keys of a validator into its session keys.
+pub fn session_keys(babe: BabeId, grandpa: GrandpaId) -> SessionKeys {
+	SessionKeys { babe, grandpa }
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or("Development wasm binary not available".to_string())?;
 
@@ -61,7 +115,11 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Bob"),
 				get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-			],
+			].into_iter().map(|k| (k, ENDOWMENT)).collect(),
+			// Initial claims
+			vec![],
+			// Vesting schedules
+			vec![],
 			true,
 		),
 		// Bootnodes
@@ -71,9 +129,9 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		// Protocol ID
 		None,
 		// Properties
-		None,
+		Some(properties()),
 		// Extensions
-		None,
+		Default::default(),
 	))
 }
 
@@ -109,6 +167,13 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+			].into_iter().map(|k| (k, ENDOWMENT)).collect(),
+			// Initial claims
+			vec![],
+			// Vesting schedules: Ferdie keeps a quarter liquid and unlocks the rest block by
+			// block over 30 days, after a one day cliff.
+			vec![
+				(get_account_id_from_seed::<sr25519::Public>("Ferdie"), DAYS, 30 * DAYS, ENDOWMENT / 4),
 			],
 			true,
 		),
@@ -119,18 +184,34 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		// Protocol ID
 		None,
 		// Properties
-		None,
+		Some(properties()),
 		// Extensions
-		None,
+		Default::default(),
+	))
+}
+
+/// The raw staging spec written by `make staging-spec`.
+const STAGING_SPEC: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/res/staging-raw.json");
+
+/// The staging network. Every node must start from the same raw spec, generated once by
+/// `make staging-spec` from the validators' real keys in `node/res/staging.toml`, since a
+/// rebuilt runtime would change the genesis hash.
+pub fn staging_config() -> Result<ChainSpec, String> {
+	ChainSpec::from_json_file(STAGING_SPEC.into()).map_err(|e| format!(
+		"Error loading the staging spec `{}`: {}. Generate it with `make staging-spec` or pass \
+		the raw spec file to `--chain`",
+		STAGING_SPEC, e,
 	))
 }
 
 /// Configure initial storage state for FRAME modules.
-fn testnet_genesis(
+pub fn testnet_genesis(
 	wasm_binary: &[u8],
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	initial_authorities: Vec<(AccountId, BabeId, GrandpaId)>,
 	root_key: AccountId,
-	endowed_accounts: Vec<AccountId>,
+	endowed_accounts: Vec<(AccountId, Balance)>,
+	initial_claims: Vec<(Vec<u8>, AccountId)>,
+	vesting: Vec<(AccountId, BlockNumber, BlockNumber, Balance)>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -140,14 +221,41 @@ fn testnet_genesis(
 			changes_trie_config: Default::default(),
 		}),
 		pallet_balances: Some(BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60)).collect(),
+			// Configure endowed accounts with their initial balance.
+			balances: endowed_accounts,
 		}),
-		pallet_aura: Some(AuraConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
+		pallet_validator_set: Some(ValidatorSetConfig {
+			validators: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+		}),
+		pallet_session: Some(SessionConfig {
+			keys: initial_authorities.iter().map(|x| {
+				(x.0.clone(), x.0.clone(), session_keys(x.1.clone(), x.2.clone()))
+			}).collect(),
+		}),
+		// BABE and Grandpa authorities are initialized by the session from the keys above.
+		pallet_babe: Some(BabeConfig {
+			authorities: vec![],
 		}),
 		pallet_grandpa: Some(GrandpaConfig {
-			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
+			authorities: vec![],
+		}),
+		pallet_treasury: Some(Default::default()),
+		// Each schedule locks all but `liquid` of the endowment from `begin` on and then
+		// unlocks it linearly over `length` blocks. [who, begin, length, liquid]
+		pallet_vesting: Some(VestingConfig {
+			vesting,
+		}),
+		pallet_poe: Some(PoeModuleConfig {
+			claims: initial_claims,
+		}),
+		// The initial validators form the council until root is handed over to governance.
+		pallet_collective_Instance1: Some(CouncilConfig {
+			members: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+			phantom: Default::default(),
+		}),
+		pallet_democracy: Some(DemocracyConfig::default()),
+		pallet_contracts: Some(ContractsConfig {
+			current_schedule: Default::default(),
 		}),
 		pallet_sudo: Some(SudoConfig {
 			// Assign network admin rights.
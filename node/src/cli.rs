@@ -1,3 +1,4 @@
+use std::str::FromStr;
 use structopt::StructOpt;
 use sc_cli::RunCmd;
 
@@ -8,6 +9,57 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	#[structopt(flatten)]
+	pub finality: FinalityOverrides,
+
+	/// Replace BABE and GRANDPA with manual-seal block production for development:
+	/// `instant` seals a block for every transaction, `manual` waits for the
+	/// `engine_createBlock` RPC and a number seals a block every that many milliseconds.
+	#[structopt(long, value_name = "instant|manual|MILLIS")]
+	pub sealing: Option<Sealing>,
+}
+
+/// Block production mode of a development node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction lands in the pool.
+	Instant,
+	/// Seal blocks only on `engine_createBlock` RPC calls.
+	Manual,
+	/// Seal a block at a fixed interval, in milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"instant" => Sealing::Instant,
+			"manual" => Sealing::Manual,
+			millis => Sealing::Interval(millis.parse().map_err(|_| {
+				format!("expected `instant`, `manual` or an interval in milliseconds, got `{}`", s)
+			})?),
+		})
+	}
+}
+
+/// Command line overrides for the GRANDPA parameters of the chain spec.
+#[derive(Debug, Clone, Default, StructOpt)]
+pub struct FinalityOverrides {
+	/// Interval between GRANDPA gossip rounds, in milliseconds.
+	#[structopt(long = "grandpa-gossip-duration", value_name = "MILLIS")]
+	pub gossip_duration_millis: Option<u64>,
+
+	/// Number of blocks between justifications persisted to the database.
+	#[structopt(long = "grandpa-justification-period", value_name = "BLOCKS")]
+	pub justification_period: Option<u32>,
+
+	/// Whether this node, unless it is an authority, runs the GRANDPA observer instead of the
+	/// full voter. Authorities always vote.
+	#[structopt(long = "grandpa-observer", value_name = "BOOL")]
+	pub observer_enabled: Option<bool>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -15,6 +67,12 @@ pub enum Subcommand {
 	/// Build a chain specification.
 	BuildSpec(sc_cli::BuildSpecCmd),
 
+	/// Generate a chain specification from a TOML or JSON genesis configuration.
+	GenerateSpec(crate::generate_spec::GenerateSpecCmd),
+
+	/// Manage the session keys in the node's keystore.
+	Key(crate::key::KeyCmd),
+
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
 
@@ -27,12 +85,18 @@ pub enum Subcommand {
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
+	/// Export, verify or import a compressed snapshot of a block's state.
+	Snapshot(crate::snapshot::SnapshotCmd),
+
 	/// Remove the whole chain.
 	PurgeChain(sc_cli::PurgeChainCmd),
 
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Check a runtime upgrade against a state snapshot written by `export-state`.
+	TryRuntime(crate::try_runtime::TryRuntimeCmd),
+
 	/// The custom benchmark subcommmand benchmarking runtime pallets.
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
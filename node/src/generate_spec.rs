@@ -0,0 +1,469 @@
+//! The `generate-spec` subcommand: builds a chain spec from a TOML or JSON description of the
+//! genesis authorities, balances, claims and vesting instead of the hard-coded development presets.
+
+use std::{convert::TryFrom, fmt, fs, path::{Path, PathBuf}};
+use serde::{Deserialize, Deserializer, de::{self, Visitor}};
+use structopt::StructOpt;
+use sp_core::{sr25519, ed25519, crypto::{Public, Ss58Codec}};
+use sc_service::{ChainType, Properties, config::MultiaddrWithPeerId};
+use sc_telemetry::TelemetryEndpoints;
+use node_template_runtime::{AccountId, Balance, BlockNumber, ExistentialDeposit, MaxClaimLength, WASM_BINARY};
+use crate::chain_spec::{ChainSpec, testnet_genesis, properties};
+
+/// The `generate-spec` command used to build a chain spec from a configuration file.
+#[derive(Debug, StructOpt)]
+pub struct GenerateSpecCmd {
+	/// Path to the TOML or JSON genesis configuration.
+	#[structopt(parse(from_os_str))]
+	pub config: PathBuf,
+
+	/// Output the chain spec in raw storage format.
+	#[structopt(long = "raw")]
+	pub raw: bool,
+
+	/// Write the chain spec to this file instead of stdout.
+	#[structopt(long, short, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+}
+
+/// Genesis description read by `generate-spec`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpecConfig {
+	/// Human readable name of the chain.
+	pub name: String,
+	/// Identifier of the chain, also used as the base path directory name.
+	pub id: String,
+	/// Type of the chain.
+	#[serde(default = "default_chain_type")]
+	pub chain_type: ChainType,
+	/// Network protocol id, which must be unique to the chain.
+	#[serde(default)]
+	pub protocol_id: Option<String>,
+	/// Bootnode multiaddrs including the `/p2p/<peer id>` suffix.
+	#[serde(default)]
+	pub boot_nodes: Vec<String>,
+	/// Telemetry servers nodes of the chain report to.
+	#[serde(default)]
+	pub telemetry_endpoints: Vec<TelemetryConfig>,
+	/// Initial validators with their session keys.
+	pub authorities: Vec<AuthorityConfig>,
+	/// The sudo account.
+	pub sudo: String,
+	/// Pre-funded accounts.
+	#[serde(default)]
+	pub endowed: Vec<EndowedConfig>,
+	/// Token properties such as `tokenSymbol` and `tokenDecimals`, overriding the defaults.
+	#[serde(default)]
+	pub properties: Properties,
+	/// Claims registered at genesis.
+	#[serde(default)]
+	pub claims: Vec<ClaimConfig>,
+	/// Vesting schedules over endowed accounts.
+	#[serde(default)]
+	pub vesting: Vec<VestingConfig>,
+}
+
+/// A genesis validator. Keys are given as SS58 addresses or `0x` prefixed hex.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuthorityConfig {
+	/// The validator account.
+	pub account: String,
+	/// The sr25519 BABE key.
+	pub babe: String,
+	/// The ed25519 GRANDPA key.
+	pub grandpa: String,
+}
+
+/// A telemetry server.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TelemetryConfig {
+	/// The server's websocket URL or multiaddr.
+	pub url: String,
+	/// The most verbose level of telemetry messages sent to it.
+	#[serde(default)]
+	pub verbosity: u8,
+}
+
+/// A pre-funded account.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EndowedConfig {
+	/// The account to fund.
+	pub account: String,
+	/// Its initial free balance.
+	#[serde(deserialize_with = "deserialize_balance")]
+	pub balance: Balance,
+}
+
+/// A claim registered at genesis.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClaimConfig {
+	/// The owner of the claim.
+	pub owner: String,
+	/// The `0x` prefixed hex encoded proof.
+	pub proof: String,
+}
+
+/// A vesting schedule over an endowed account. Exactly one of `length` and `per_block` must be
+/// given.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VestingConfig {
+	/// The vesting account, which must be endowed.
+	pub account: String,
+	/// The block before which nothing unlocks.
+	#[serde(default)]
+	pub cliff: BlockNumber,
+	/// The number of blocks over which the locked amount unlocks after the cliff.
+	#[serde(default)]
+	pub length: Option<BlockNumber>,
+	/// The amount unlocked every block after the cliff.
+	#[serde(default, deserialize_with = "deserialize_optional_balance")]
+	pub per_block: Option<Balance>,
+	/// The part of the endowment that is never locked.
+	#[serde(default, deserialize_with = "deserialize_balance")]
+	pub liquid: Balance,
+}
+
+impl VestingConfig {
+	/// The number of blocks over which an endowment of `balance` unlocks. A `per_block` amount
+	/// that doesn't divide the locked balance rounds up to one more block.
+	pub fn length(&self, balance: Balance) -> Result<BlockNumber, String> {
+		if self.liquid > balance {
+			return Err(format!("Liquid amount of `{}` exceeds its endowment", self.account));
+		}
+		let locked = balance - self.liquid;
+		match (self.length, self.per_block) {
+			(Some(length), None) => Ok(length),
+			(None, Some(per_block)) if per_block > 0 => {
+				let blocks = (locked + per_block - 1) / per_block;
+				BlockNumber::try_from(blocks)
+					.map_err(|_| format!("Vesting of `{}` takes too many blocks", self.account))
+			},
+			_ => Err(format!(
+				"Vesting of `{}` needs either `length` or a non-zero `per_block`", self.account,
+			)),
+		}
+	}
+}
+
+/// Accepts balances as integers or as decimal strings. TOML integers are limited to `i64` and
+/// JSON numbers beyond `u64` lose precision, so larger balances have to be given as strings.
+struct BalanceVisitor;
+
+impl<'de> Visitor<'de> for BalanceVisitor {
+	type Value = Balance;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a non-negative integer or a decimal string")
+	}
+
+	fn visit_u64<E: de::Error>(self, v: u64) -> Result<Balance, E> {
+		Ok(v.into())
+	}
+
+	fn visit_i64<E: de::Error>(self, v: i64) -> Result<Balance, E> {
+		u64::try_from(v).map(Into::into).map_err(|_| E::custom(format!("Negative balance `{}`", v)))
+	}
+
+	fn visit_u128<E: de::Error>(self, v: u128) -> Result<Balance, E> {
+		Ok(v)
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<Balance, E> {
+		v.parse().map_err(|e| E::custom(format!("Invalid balance `{}`: {}", v, e)))
+	}
+}
+
+fn deserialize_balance<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Balance, D::Error> {
+	deserializer.deserialize_any(BalanceVisitor)
+}
+
+fn deserialize_optional_balance<'de, D: Deserializer<'de>>(
+	deserializer: D,
+) -> Result<Option<Balance>, D::Error> {
+	deserialize_balance(deserializer).map(Some)
+}
+
+fn default_chain_type() -> ChainType {
+	ChainType::Live
+}
+
+fn decode_hex(s: &str) -> Result<Option<Vec<u8>>, String> {
+	match s.strip_prefix("0x") {
+		Some(hex_str) => hex::decode(hex_str).map(Some).map_err(|e| format!("Invalid hex `{}`: {}", s, e)),
+		None => Ok(None),
+	}
+}
+
+fn parse_public<P: Public + Ss58Codec>(s: &str) -> Result<P, String> {
+	match decode_hex(s)? {
+		Some(bytes) if bytes.len() == P::LEN => Ok(P::from_slice(&bytes)),
+		Some(_) => Err(format!("Public key `{}` has the wrong length", s)),
+		None => P::from_ss58check(s).map_err(|e| format!("Invalid public key `{}`: {:?}", s, e)),
+	}
+}
+
+fn parse_account(s: &str) -> Result<AccountId, String> {
+	match decode_hex(s)? {
+		Some(bytes) if bytes.len() == 32 => {
+			let mut raw = [0u8; 32];
+			raw.copy_from_slice(&bytes);
+			Ok(raw.into())
+		},
+		Some(_) => Err(format!("Account `{}` has the wrong length", s)),
+		None => AccountId::from_ss58check(s).map_err(|e| format!("Invalid account `{}`: {:?}", s, e)),
+	}
+}
+
+impl SpecConfig {
+	/// Read a configuration, choosing the format from the file extension.
+	pub fn from_file(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path)
+			.map_err(|e| format!("Error reading `{}`: {}", path.display(), e))?;
+		match path.extension().and_then(|ext| ext.to_str()) {
+			Some("toml") => toml::from_str(&content).map_err(|e| format!("Invalid TOML config: {}", e)),
+			_ => serde_json::from_str(&content).map_err(|e| format!("Invalid JSON config: {}", e)),
+		}
+	}
+
+	/// The telemetry endpoints of the chain, if any are configured.
+	pub fn telemetry(&self) -> Result<Option<TelemetryEndpoints>, String> {
+		if self.telemetry_endpoints.is_empty() {
+			return Ok(None);
+		}
+		let endpoints = self.telemetry_endpoints.iter()
+			.map(|t| (t.url.clone(), t.verbosity))
+			.collect();
+		TelemetryEndpoints::new(endpoints)
+			.map(Some)
+			.map_err(|e| format!("Invalid telemetry endpoint: {}", e))
+	}
+
+	/// Build the chain spec described by this configuration, rejecting genesis state the runtime
+	/// would refuse or reap: over-long or duplicate claims and endowments below the existential
+	/// deposit.
+	pub fn into_chain_spec(self) -> Result<ChainSpec, String> {
+		let authorities = self.authorities.iter()
+			.map(|a| Ok((
+				parse_account(&a.account)?,
+				parse_public::<sr25519::Public>(&a.babe)?.into(),
+				parse_public::<ed25519::Public>(&a.grandpa)?.into(),
+			)))
+			.collect::<Result<Vec<_>, String>>()?;
+		if authorities.is_empty() {
+			return Err("At least one authority is required".into());
+		}
+		let root_key = parse_account(&self.sudo)?;
+		let endowed = self.endowed.iter()
+			.map(|e| {
+				if e.balance < ExistentialDeposit::get() {
+					return Err(format!(
+						"Endowment of `{}` is below the existential deposit of {}",
+						e.account, ExistentialDeposit::get(),
+					));
+				}
+				Ok((parse_account(&e.account)?, e.balance))
+			})
+			.collect::<Result<Vec<_>, String>>()?;
+		let mut claims: Vec<(Vec<u8>, AccountId)> = Vec::new();
+		for c in &self.claims {
+			let proof = decode_hex(&c.proof)?
+				.ok_or_else(|| format!("Proof `{}` must be 0x prefixed hex", c.proof))?;
+			if proof.len() > MaxClaimLength::get() as usize {
+				return Err(format!("Proof `{}` is longer than {} bytes", c.proof, MaxClaimLength::get()));
+			}
+			if claims.iter().any(|(claimed, _)| claimed == &proof) {
+				return Err(format!("Proof `{}` is claimed twice", c.proof));
+			}
+			claims.push((proof, parse_account(&c.owner)?));
+		}
+		let vesting = self.vesting.iter()
+			.map(|v| {
+				let who = parse_account(&v.account)?;
+				let balance = endowed.iter()
+					.find(|(account, _)| account == &who)
+					.map(|(_, balance)| *balance)
+					.ok_or_else(|| format!("Vesting account `{}` is not endowed", v.account))?;
+				Ok((who, v.cliff, v.length(balance)?, v.liquid))
+			})
+			.collect::<Result<Vec<_>, String>>()?;
+		let mut spec_properties = properties();
+		spec_properties.extend(self.properties);
+		let boot_nodes = self.boot_nodes.iter()
+			.map(|addr| addr.parse::<MultiaddrWithPeerId>()
+				.map_err(|e| format!("Invalid bootnode `{}`: {}", addr, e)))
+			.collect::<Result<Vec<_>, String>>()?;
+		let telemetry = self.telemetry()?;
+
+		let wasm_binary = WASM_BINARY.ok_or("Wasm binary not available".to_string())?;
+		Ok(ChainSpec::from_genesis(
+			&self.name,
+			&self.id,
+			self.chain_type,
+			move || testnet_genesis(
+				wasm_binary,
+				authorities.clone(),
+				root_key.clone(),
+				endowed.clone(),
+				claims.clone(),
+				vesting.clone(),
+				false,
+			),
+			boot_nodes,
+			telemetry,
+			self.protocol_id.as_deref(),
+			Some(spec_properties),
+			Default::default(),
+		))
+	}
+}
+
+impl GenerateSpecCmd {
+	/// Run the command.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let spec = SpecConfig::from_file(&self.config)?.into_chain_spec()?;
+		let json = sc_service::ChainSpec::as_json(&spec, self.raw)?;
+
+		match &self.output {
+			Some(path) => fs::write(path, json)?,
+			None => println!("{}", json),
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CONFIG: &str = r#"
+		name = "Poe Testnet"
+		id = "poe_testnet"
+		sudo = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+
+		[[authorities]]
+		account = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+		babe = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+		grandpa = "5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu"
+
+		[[endowed]]
+		account = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+		balance = "1000000000000000000000000"
+
+		[[endowed]]
+		account = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+		balance = 1000
+
+		[[vesting]]
+		account = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+		per_block = "300"
+		liquid = 100
+	"#;
+
+	fn vesting(
+		length: Option<BlockNumber>,
+		per_block: Option<Balance>,
+		liquid: Balance,
+	) -> VestingConfig {
+		VestingConfig { account: "alice".into(), cliff: 0, length, per_block, liquid }
+	}
+
+	#[test]
+	fn toml_balances_parse_from_integers_and_strings() {
+		let config: SpecConfig = toml::from_str(CONFIG).unwrap();
+		assert_eq!(config.endowed[0].balance, 1_000_000_000_000_000_000_000_000);
+		assert_eq!(config.endowed[1].balance, 1000);
+		assert_eq!(config.vesting[0].per_block, Some(300));
+		assert_eq!(config.vesting[0].liquid, 100);
+		assert_eq!(config.vesting[0].length, None);
+	}
+
+	#[test]
+	fn toml_rejects_invalid_balances() {
+		let negative = CONFIG.replace("balance = 1000", "balance = -1");
+		assert!(toml::from_str::<SpecConfig>(&negative).is_err());
+		let garbage = CONFIG.replace("balance = 1000", "balance = \"ten\"");
+		assert!(toml::from_str::<SpecConfig>(&garbage).is_err());
+	}
+
+	#[test]
+	fn staging_config_parses() {
+		let config: SpecConfig = toml::from_str(include_str!("../res/staging.toml")).unwrap();
+		assert_eq!(config.protocol_id.as_deref(), Some("poe-staging"));
+		assert_eq!(config.authorities.len(), 2);
+		assert!(!config.boot_nodes.is_empty());
+		assert!(config.telemetry().unwrap().is_some());
+	}
+
+	#[test]
+	fn telemetry_endpoints_are_optional_and_checked() {
+		let config: SpecConfig = toml::from_str(CONFIG).unwrap();
+		assert!(config.telemetry().unwrap().is_none());
+
+		let with_telemetry = format!("{}\n[[telemetry_endpoints]]\nurl = \"{}\"\n", CONFIG, "wss://telemetry.example.com/submit/");
+		let config: SpecConfig = toml::from_str(&with_telemetry).unwrap();
+		assert_eq!(config.telemetry_endpoints[0].verbosity, 0);
+		assert!(config.telemetry().unwrap().is_some());
+
+		let invalid = with_telemetry.replace("wss://telemetry.example.com/submit/", "not a url");
+		let config: SpecConfig = toml::from_str(&invalid).unwrap();
+		assert!(config.telemetry().is_err());
+	}
+
+	fn with_claims(proofs: &[String]) -> String {
+		proofs.iter().fold(CONFIG.to_string(), |config, proof| format!(
+			"{}\n[[claims]]\nowner = \"5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty\"\nproof = \"{}\"\n",
+			config, proof,
+		))
+	}
+
+	fn chain_spec_error(config: &str) -> String {
+		toml::from_str::<SpecConfig>(config).unwrap().into_chain_spec().err().unwrap()
+	}
+
+	#[test]
+	fn long_claims_are_rejected() {
+		let long = format!("0x{}", "ab".repeat(MaxClaimLength::get() as usize + 1));
+		let error = chain_spec_error(&with_claims(&["0x01".into(), long.clone()]));
+		assert!(error.contains(&long), "{}", error);
+	}
+
+	#[test]
+	fn duplicate_claims_are_rejected() {
+		let error = chain_spec_error(&with_claims(&["0x01".into(), "0x02".into(), "0x01".into()]));
+		assert_eq!(error, "Proof `0x01` is claimed twice");
+	}
+
+	#[test]
+	fn endowments_below_existential_deposit_are_rejected() {
+		let config = CONFIG.replace("balance = 1000", &format!("balance = {}", ExistentialDeposit::get() - 1));
+		let error = chain_spec_error(&config);
+		assert!(error.contains("5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"), "{}", error);
+	}
+
+	#[test]
+	fn per_block_rounds_length_up() {
+		assert_eq!(vesting(None, Some(300), 100).length(1000), Ok(3));
+		assert_eq!(vesting(None, Some(300), 0).length(1000), Ok(4));
+		assert_eq!(vesting(None, Some(250), 0).length(1000), Ok(4));
+		assert_eq!(vesting(Some(10), None, 0).length(1000), Ok(10));
+	}
+
+	#[test]
+	fn zero_per_block_is_rejected() {
+		assert!(vesting(None, Some(0), 0).length(1000).is_err());
+		assert!(vesting(None, None, 0).length(1000).is_err());
+		assert!(vesting(Some(10), Some(100), 0).length(1000).is_err());
+	}
+
+	#[test]
+	fn liquid_above_balance_is_rejected() {
+		assert!(vesting(None, Some(100), 1001).length(1000).is_err());
+		assert_eq!(vesting(None, Some(100), 1000).length(1000), Ok(0));
+	}
+}
@@ -0,0 +1,225 @@
+//! The `key` subcommands: manage the session keys in the node's keystore without `subkey` or
+//! the `author_insertKey` RPC, and print the encoded keys expected by `session.set_keys`.
+
+use std::str::FromStr;
+use codec::Encode;
+use structopt::StructOpt;
+use sc_cli::{CliConfiguration, KeystoreParams, SharedParams, SubstrateCli};
+use sc_keystore::LocalKeystore;
+use sc_service::{Configuration, config::KeystoreConfig};
+use sp_core::{Pair, crypto::Ss58Codec};
+use sp_keystore::SyncCryptoStore;
+use sp_consensus_babe::{AuthorityId as BabeId, AuthorityPair as BabePair};
+use sp_finality_grandpa::{AuthorityId as GrandpaId, AuthorityPair as GrandpaPair};
+use node_template_runtime::opaque::SessionKeys;
+use crate::cli::Cli;
+
+/// Key management subcommands working on the keystore of the selected chain.
+#[derive(Debug, StructOpt)]
+pub enum KeyCmd {
+	/// Generate new BABE and GRANDPA keys in the keystore and print the encoded session keys.
+	GenerateSessionKeys(GenerateSessionKeysCmd),
+
+	/// Insert a key derived from a secret URI into the keystore.
+	Insert(InsertCmd),
+
+	/// List the session keys held by the keystore.
+	Inspect(InspectCmd),
+}
+
+/// A session key type, by its four letter key type id.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionKeyType {
+	/// The sr25519 BABE block authoring key.
+	Babe,
+	/// The ed25519 GRANDPA finality key.
+	Grandpa,
+}
+
+impl FromStr for SessionKeyType {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"babe" => Ok(SessionKeyType::Babe),
+			"gran" => Ok(SessionKeyType::Grandpa),
+			_ => Err(format!("expected `babe` or `gran`, got `{}`", s)),
+		}
+	}
+}
+
+/// The `key generate-session-keys` command.
+#[derive(Debug, StructOpt)]
+pub struct GenerateSessionKeysCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub keystore_params: KeystoreParams,
+}
+
+/// The `key insert` command.
+#[derive(Debug, StructOpt)]
+pub struct InsertCmd {
+	/// The secret URI the key is derived from, e.g. a mnemonic phrase or `//Alice`.
+	#[structopt(long)]
+	pub suri: String,
+
+	/// The key type to insert.
+	#[structopt(long, value_name = "babe|gran")]
+	pub key_type: SessionKeyType,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub keystore_params: KeystoreParams,
+}
+
+/// The `key inspect` command.
+#[derive(Debug, StructOpt)]
+pub struct InspectCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub keystore_params: KeystoreParams,
+}
+
+macro_rules! impl_cli_configuration {
+	($($cmd:ty),*) => {$(
+		impl CliConfiguration for $cmd {
+			fn shared_params(&self) -> &SharedParams {
+				&self.shared_params
+			}
+
+			fn keystore_params(&self) -> Option<&KeystoreParams> {
+				Some(&self.keystore_params)
+			}
+		}
+	)*}
+}
+
+impl_cli_configuration!(GenerateSessionKeysCmd, InsertCmd, InspectCmd);
+
+/// Open the on-disk keystore of the node configuration.
+fn open_keystore(config: &Configuration) -> sc_cli::Result<LocalKeystore> {
+	match &config.keystore {
+		KeystoreConfig::Path { path, password } => LocalKeystore::open(path.clone(), password.clone())
+			.map_err(|e| format!("Error opening keystore `{}`: {}", path.display(), e).into()),
+		KeystoreConfig::InMemory => Err("Key commands need an on-disk keystore".into()),
+	}
+}
+
+/// A public key as `0x` prefixed hex followed by its SS58 address in the chain's format.
+fn display_key<K: AsRef<[u8]> + Ss58Codec>(key: &K) -> String {
+	format!("0x{} ({})", hex::encode(key), key.to_ss58check())
+}
+
+/// The `0x` prefixed SCALE encoding of the session keys, as passed to `session.set_keys`.
+fn encode_session_keys(babe: BabeId, grandpa: GrandpaId) -> String {
+	format!("0x{}", hex::encode(SessionKeys { babe, grandpa }.encode()))
+}
+
+fn keystore_error(e: sp_keystore::Error) -> sc_cli::Error {
+	format!("Keystore error: {}", e).into()
+}
+
+impl KeyCmd {
+	/// Run the selected subcommand.
+	pub fn run(&self, cli: &Cli) -> sc_cli::Result<()> {
+		match self {
+			KeyCmd::GenerateSessionKeys(cmd) => {
+				let runner = cli.create_runner(cmd)?;
+				runner.sync_run(|config| cmd.run(&config))
+			},
+			KeyCmd::Insert(cmd) => {
+				let runner = cli.create_runner(cmd)?;
+				runner.sync_run(|config| cmd.run(&config))
+			},
+			KeyCmd::Inspect(cmd) => {
+				let runner = cli.create_runner(cmd)?;
+				runner.sync_run(|config| cmd.run(&config))
+			},
+		}
+	}
+}
+
+impl GenerateSessionKeysCmd {
+	/// Run the command.
+	pub fn run(&self, config: &Configuration) -> sc_cli::Result<()> {
+		let keystore = open_keystore(config)?;
+		let babe = BabeId::from(
+			keystore.sr25519_generate_new(sp_consensus_babe::KEY_TYPE, None).map_err(keystore_error)?,
+		);
+		let grandpa = GrandpaId::from(
+			keystore.ed25519_generate_new(sp_finality_grandpa::KEY_TYPE, None).map_err(keystore_error)?,
+		);
+
+		println!("babe: {}", display_key(&babe));
+		println!("gran: {}", display_key(&grandpa));
+		println!("Session keys: {}", encode_session_keys(babe, grandpa));
+		Ok(())
+	}
+}
+
+impl InsertCmd {
+	/// Run the command.
+	pub fn run(&self, config: &Configuration) -> sc_cli::Result<()> {
+		let keystore = open_keystore(config)?;
+		let invalid_suri = |_| sc_cli::Error::from(format!("Invalid secret URI `{}`", self.suri));
+		let (key_type, public, display) = match self.key_type {
+			SessionKeyType::Babe => {
+				let public = BabePair::from_string(&self.suri, None).map_err(invalid_suri)?.public();
+				(sp_consensus_babe::KEY_TYPE, public.encode(), display_key(&public))
+			},
+			SessionKeyType::Grandpa => {
+				let public = GrandpaPair::from_string(&self.suri, None).map_err(invalid_suri)?.public();
+				(sp_finality_grandpa::KEY_TYPE, public.encode(), display_key(&public))
+			},
+		};
+		SyncCryptoStore::insert_unknown(&keystore, key_type, &self.suri, &public)
+			.map_err(|_| sc_cli::Error::from("Keystore error: failed to insert the key"))?;
+
+		println!("Inserted {}", display);
+		Ok(())
+	}
+}
+
+impl InspectCmd {
+	/// Run the command.
+	pub fn run(&self, config: &Configuration) -> sc_cli::Result<()> {
+		let keystore = open_keystore(config)?;
+		let babe: Vec<BabeId> = keystore.sr25519_public_keys(sp_consensus_babe::KEY_TYPE)
+			.into_iter()
+			.map(Into::into)
+			.collect();
+		let grandpa: Vec<GrandpaId> = keystore.ed25519_public_keys(sp_finality_grandpa::KEY_TYPE)
+			.into_iter()
+			.map(Into::into)
+			.collect();
+
+		for key in &babe {
+			println!("babe: {}", display_key(key));
+		}
+		for key in &grandpa {
+			println!("gran: {}", display_key(key));
+		}
+
+		match (babe.as_slice(), grandpa.as_slice()) {
+			([babe], [grandpa]) => {
+				println!("Session keys: {}", encode_session_keys(babe.clone(), grandpa.clone()));
+			},
+			_ => println!(
+				"Session keys are only printed when the keystore holds exactly one key of each type",
+			),
+		}
+		Ok(())
+	}
+}
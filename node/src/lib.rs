@@ -1,3 +1,8 @@
 pub mod chain_spec;
+pub mod cli;
+pub mod generate_spec;
+pub mod key;
+pub mod try_runtime;
 pub mod service;
+pub mod snapshot;
 pub mod rpc;
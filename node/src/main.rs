@@ -2,6 +2,10 @@
 #![warn(missing_docs)]
 
 mod chain_spec;
+mod generate_spec;
+mod key;
+mod snapshot;
+mod try_runtime;
 #[macro_use]
 mod service;
 mod cli;
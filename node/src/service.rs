@@ -2,14 +2,17 @@
 
 use std::sync::Arc;
 use std::time::Duration;
+use futures::{StreamExt, stream};
 use sc_client_api::{ExecutorProvider, RemoteBackend};
 use node_template_runtime::{self, opaque::Block, RuntimeApi};
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
 use sp_inherents::InherentDataProviders;
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
-use sp_consensus_aura::sr25519::{AuthorityPair as AuraPair};
-use sc_finality_grandpa::{FinalityProofProvider as GrandpaFinalityProofProvider, SharedVoterState};
+use sc_finality_grandpa::SharedVoterState;
+use crate::chain_spec::FinalityParams;
+use crate::cli::{FinalityOverrides, Sealing};
+use sc_consensus_manual_seal::{EngineCommand, ManualSealParams, consensus::babe::BabeConsensusDataProvider};
 
 // Our native executor instance.
 native_executor_instance!(
@@ -28,18 +31,18 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 	sp_consensus::DefaultImportQueue<Block, FullClient>,
 	sc_transaction_pool::FullPool<Block, FullClient>,
 	(
-		sc_consensus_aura::AuraBlockImport<
+		sc_consensus_babe::BabeBlockImport<
 			Block,
 			FullClient,
 			sc_finality_grandpa::GrandpaBlockImport<FullBackend, Block, FullClient, FullSelectChain>,
-			AuraPair
 		>,
-		sc_finality_grandpa::LinkHalf<Block, FullClient, FullSelectChain>
+		sc_consensus_babe::BabeLink<Block>,
+		sc_finality_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
 	)
 >, ServiceError> {
 	let inherent_data_providers = sp_inherents::InherentDataProviders::new();
 
-	let (client, backend, keystore, task_manager) =
+	let (client, backend, keystore_container, task_manager) =
 		sc_service::new_full_parts::<Block, RuntimeApi, Executor>(&config)?;
 	let client = Arc::new(client);
 
@@ -47,6 +50,7 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 
 	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
 		config.transaction_pool.clone(),
+		config.role.is_authority().into(),
 		config.prometheus_registry(),
 		task_manager.spawn_handle(),
 		client.clone(),
@@ -56,16 +60,20 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		client.clone(), &(client.clone() as Arc<_>), select_chain.clone(),
 	)?;
 
-	let aura_block_import = sc_consensus_aura::AuraBlockImport::<_, _, _, AuraPair>::new(
-		grandpa_block_import.clone(), client.clone(),
-	);
+	let justification_import = grandpa_block_import.clone();
+
+	let (babe_block_import, babe_link) = sc_consensus_babe::block_import(
+		sc_consensus_babe::Config::get_or_compute(&*client)?,
+		grandpa_block_import,
+		client.clone(),
+	)?;
 
-	let import_queue = sc_consensus_aura::import_queue::<_, _, _, AuraPair, _, _>(
-		sc_consensus_aura::slot_duration(&*client)?,
-		aura_block_import.clone(),
-		Some(Box::new(grandpa_block_import.clone())),
-		None,
+	let import_queue = sc_consensus_babe::import_queue(
+		babe_link.clone(),
+		babe_block_import.clone(),
+		Some(Box::new(justification_import)),
 		client.clone(),
+		select_chain.clone(),
 		inherent_data_providers.clone(),
 		&task_manager.spawn_handle(),
 		config.prometheus_registry(),
@@ -73,22 +81,54 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 	)?;
 
 	Ok(sc_service::PartialComponents {
-		client, backend, task_manager, import_queue, keystore, select_chain, transaction_pool,
+		client, backend, task_manager, import_queue, keystore_container, select_chain, transaction_pool,
 		inherent_data_providers,
-		other: (aura_block_import, grandpa_link),
+		other: (babe_block_import, babe_link, grandpa_link),
 	})
 }
 
+/// Reads the GRANDPA parameters from the chain spec, falling back to the defaults for specs
+/// without the extension, and applies the command line overrides on top.
+pub fn finality_params(
+	config: &Configuration,
+	overrides: &FinalityOverrides,
+) -> Result<FinalityParams, ServiceError> {
+	let mut params = sc_chain_spec::get_extension::<FinalityParams>(config.chain_spec.extensions())
+		.cloned()
+		.unwrap_or_default();
+
+	if let Some(gossip_duration_millis) = overrides.gossip_duration_millis {
+		params.gossip_duration_millis = gossip_duration_millis;
+	}
+	if let Some(justification_period) = overrides.justification_period {
+		params.justification_period = justification_period;
+	}
+	if let Some(observer_enabled) = overrides.observer_enabled {
+		params.observer_enabled = observer_enabled;
+	}
+
+	if params.gossip_duration_millis == 0 || params.justification_period == 0 {
+		return Err(ServiceError::Other(
+			"GRANDPA gossip duration and justification period must be positive".into(),
+		));
+	}
+	Ok(params)
+}
+
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_full(
+	mut config: Configuration,
+	finality_overrides: &FinalityOverrides,
+) -> Result<TaskManager, ServiceError> {
+	let finality = finality_params(&config, finality_overrides)?;
+
 	let sc_service::PartialComponents {
-		client, backend, mut task_manager, import_queue, keystore, select_chain, transaction_pool,
-		inherent_data_providers,
-		other: (block_import, grandpa_link),
+		client, backend, mut task_manager, import_queue, keystore_container, select_chain,
+		transaction_pool, inherent_data_providers,
+		other: (block_import, babe_link, grandpa_link),
 	} = new_partial(&config)?;
 
-	let finality_proof_provider =
-		GrandpaFinalityProofProvider::new_for_service(backend.clone(), client.clone());
+	config.network.extra_sets.push(sc_finality_grandpa::grandpa_peers_set_config());
 
 	let (network, network_status_sinks, system_rpc_tx, network_starter) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
@@ -99,8 +139,6 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 			import_queue,
 			on_demand: None,
 			block_announce_validator_builder: None,
-			finality_proof_request_builder: None,
-			finality_proof_provider: Some(finality_proof_provider.clone()),
 		})?;
 
 	if config.offchain_worker.enabled {
@@ -111,10 +149,10 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 
 	let role = config.role.clone();
 	let force_authoring = config.force_authoring;
+	let backoff_authoring_blocks: Option<()> = None;
 	let name = config.network.node_name.clone();
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
-	let telemetry_connection_sinks = sc_service::TelemetryConnectionSinks::default();
 
 	let rpc_extensions_builder = {
 		let client = client.clone();
@@ -125,27 +163,30 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 				client: client.clone(),
 				pool: pool.clone(),
 				deny_unsafe,
+				command_sink: None,
 			};
 
 			crate::rpc::create_full(deps)
 		})
 	};
 
-	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
-		network: network.clone(),
-		client: client.clone(),
-		keystore: keystore.clone(),
-		task_manager: &mut task_manager,
-		transaction_pool: transaction_pool.clone(),
-		telemetry_connection_sinks: telemetry_connection_sinks.clone(),
-		rpc_extensions_builder: rpc_extensions_builder,
-		on_demand: None,
-		remote_blockchain: None,
-		backend, network_status_sinks, system_rpc_tx, config,
-	})?;
+	let (_rpc_handlers, telemetry_connection_notifier) = sc_service::spawn_tasks(
+		sc_service::SpawnTasksParams {
+			network: network.clone(),
+			client: client.clone(),
+			keystore: keystore_container.sync_keystore(),
+			task_manager: &mut task_manager,
+			transaction_pool: transaction_pool.clone(),
+			rpc_extensions_builder: rpc_extensions_builder,
+			on_demand: None,
+			remote_blockchain: None,
+			backend, network_status_sinks, system_rpc_tx, config,
+		},
+	)?;
 
 	if role.is_authority() {
 		let proposer = sc_basic_authorship::ProposerFactory::new(
+			task_manager.spawn_handle(),
 			client.clone(),
 			transaction_pool,
 			prometheus_registry.as_ref(),
@@ -154,55 +195,61 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		let can_author_with =
 			sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone());
 
-		let aura = sc_consensus_aura::start_aura::<_, _, _, _, _, AuraPair, _, _, _>(
-			sc_consensus_aura::slot_duration(&*client)?,
-			client.clone(),
+		let babe_config = sc_consensus_babe::BabeParams {
+			keystore: keystore_container.sync_keystore(),
+			client: client.clone(),
 			select_chain,
+			env: proposer,
 			block_import,
-			proposer,
-			network.clone(),
-			inherent_data_providers.clone(),
+			sync_oracle: network.clone(),
+			inherent_data_providers: inherent_data_providers.clone(),
 			force_authoring,
-			keystore.clone(),
+			backoff_authoring_blocks,
+			babe_link,
 			can_author_with,
-		)?;
+		};
+
+		let babe = sc_consensus_babe::start_babe(babe_config)?;
 
-		// the AURA authoring task is considered essential, i.e. if it
+		// the BABE authoring task is considered essential, i.e. if it
 		// fails we take down the service with it.
-		task_manager.spawn_essential_handle().spawn_blocking("aura", aura);
+		task_manager.spawn_essential_handle().spawn_blocking("babe-proposer", babe);
 	}
 
 	// if the node isn't actively participating in consensus then it doesn't
 	// need a keystore, regardless of which protocol we use below.
 	let keystore = if role.is_authority() {
-		Some(keystore as sp_core::traits::BareCryptoStorePtr)
+		Some(keystore_container.sync_keystore())
 	} else {
 		None
 	};
 
 	let grandpa_config = sc_finality_grandpa::Config {
-		// FIXME #1578 make this available through chainspec
-		gossip_duration: Duration::from_millis(333),
-		justification_period: 512,
+		gossip_duration: Duration::from_millis(finality.gossip_duration_millis),
+		justification_period: finality.justification_period,
 		name: Some(name),
-		observer_enabled: false,
+		observer_enabled: finality.observer_enabled,
 		keystore,
 		is_authority: role.is_network_authority(),
 	};
 
-	if enable_grandpa {
-		// start the full GRANDPA voter
-		// NOTE: non-authorities could run the GRANDPA observer protocol, but at
-		// this point the full voter should provide better guarantees of block
-		// and vote data availability than the observer. The observer has not
-		// been tested extensively yet and having most nodes in a network run it
-		// could lead to finality stalls.
+	if enable_grandpa && finality.observer_enabled && !role.is_authority() {
+		// The observer follows and checks finality from the votes it receives without voting
+		// or keeping round state for other peers, which makes it cheaper than the voter but a
+		// worse source of vote data. A network whose non-authorities all observe relies on the
+		// authorities alone to gossip votes, so the chain spec leaves it off by default.
+		task_manager.spawn_essential_handle().spawn_blocking(
+			"grandpa-observer",
+			sc_finality_grandpa::run_grandpa_observer(grandpa_config, grandpa_link, network)?,
+		);
+	} else if enable_grandpa {
+		// start the full GRANDPA voter, which non-authorities run without a keystore to
+		// follow finality and relay votes.
 		let grandpa_config = sc_finality_grandpa::GrandpaParams {
 			config: grandpa_config,
 			link: grandpa_link,
 			network,
-			inherent_data_providers,
-			telemetry_on_connect: Some(telemetry_connection_sinks.on_connect_stream()),
+			telemetry_on_connect: telemetry_connection_notifier.map(|x| x.on_connect_stream()),
 			voting_rule: sc_finality_grandpa::VotingRulesBuilder::default().build(),
 			prometheus_registry,
 			shared_voter_state: SharedVoterState::empty(),
@@ -214,23 +261,165 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 			"grandpa-voter",
 			sc_finality_grandpa::run_grandpa_voter(grandpa_config)?
 		);
-	} else {
-		sc_finality_grandpa::setup_disabled_grandpa(
-			client,
-			&inherent_data_providers,
-			network,
-		)?;
 	}
 
 	network_starter.start_network();
 	Ok(task_manager)
 }
 
-/// Builds a new service for a light client.
-pub fn new_light(config: Configuration) -> Result<TaskManager, ServiceError> {
-	let (client, backend, keystore, mut task_manager, on_demand) =
+/// Builds a full development node that seals blocks with manual seal instead of the BABE
+/// authoring worker. Sealed blocks still carry a BABE pre-digest claimed with the dev keys, so
+/// authorship, epochs and sessions work as on a real chain. GRANDPA is not started. In every
+/// mode blocks can also be sealed and finalized through `engine_createBlock` and
+/// `engine_finalizeBlock`.
+pub fn new_dev(config: Configuration, sealing: Sealing) -> Result<TaskManager, ServiceError> {
+	let (client, backend, keystore_container, mut task_manager) =
+		sc_service::new_full_parts::<Block, RuntimeApi, Executor>(&config)?;
+	let client = Arc::new(client);
+
+	let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+		config.transaction_pool.clone(),
+		config.role.is_authority().into(),
+		config.prometheus_registry(),
+		task_manager.spawn_handle(),
+		client.clone(),
+	);
+
+	// Imports go through BABE so that epoch changes are tracked for the pre-digests.
+	let (block_import, babe_link) = sc_consensus_babe::block_import(
+		sc_consensus_babe::Config::get_or_compute(&*client)?,
+		client.clone(),
+		client.clone(),
+	)?;
+
+	// Registers a timestamp advancing by one slot per block and the matching BABE slot.
+	let inherent_data_providers = InherentDataProviders::new();
+	let consensus_data_provider = BabeConsensusDataProvider::new(
+		client.clone(),
+		keystore_container.sync_keystore(),
+		&inherent_data_providers,
+		babe_link.epoch_changes().clone(),
+		babe_link.config().genesis_authorities.clone(),
+	).map_err(|e| ServiceError::Other(format!("Error creating the BABE digest provider: {}", e)))?;
+
+	let import_queue = sc_consensus_manual_seal::import_queue(
+		Box::new(block_import.clone()),
+		&task_manager.spawn_handle(),
+		config.prometheus_registry(),
+	);
+
+	let (network, network_status_sinks, system_rpc_tx, network_starter) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue,
+			on_demand: None,
+			block_announce_validator_builder: None,
+		})?;
+
+	if config.offchain_worker.enabled {
+		sc_service::build_offchain_workers(
+			&config, backend.clone(), task_manager.spawn_handle(), client.clone(), network.clone(),
+		);
+	}
+
+	let prometheus_registry = config.prometheus_registry().cloned();
+	let (command_sink, commands_stream) = futures::channel::mpsc::channel(1000);
+
+	let rpc_extensions_builder = {
+		let client = client.clone();
+		let pool = transaction_pool.clone();
+
+		Box::new(move |deny_unsafe, _| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: Some(command_sink.clone()),
+			};
+
+			crate::rpc::create_full(deps)
+		})
+	};
+
+	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		network: network.clone(),
+		client: client.clone(),
+		keystore: keystore_container.sync_keystore(),
+		task_manager: &mut task_manager,
+		transaction_pool: transaction_pool.clone(),
+		rpc_extensions_builder: rpc_extensions_builder,
+		on_demand: None,
+		remote_blockchain: None,
+		backend, network_status_sinks, system_rpc_tx, config,
+	})?;
+
+	let env = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry.as_ref(),
+	);
+
+	let seal_new_block = |create_empty| EngineCommand::SealNewBlock {
+		create_empty,
+		finalize: false,
+		parent_hash: None,
+		sender: None,
+	};
+
+	// Every mode feeds its own trigger into the same command stream as the RPC.
+	let trigger_stream: stream::BoxStream<'static, _> = match sealing {
+		Sealing::Instant => transaction_pool.pool().validated_pool().import_notification_stream()
+			.map(move |_| seal_new_block(false))
+			.boxed(),
+		Sealing::Interval(millis) => stream::unfold((), move |_| async move {
+			futures_timer::Delay::new(Duration::from_millis(millis)).await;
+			Some((seal_new_block(true), ()))
+		}).boxed(),
+		Sealing::Manual => stream::pending().boxed(),
+	};
+
+	let authorship_future = sc_consensus_manual_seal::run_manual_seal(
+		ManualSealParams {
+			block_import,
+			env,
+			client,
+			pool: transaction_pool.pool().clone(),
+			commands_stream: stream::select(commands_stream, trigger_stream),
+			select_chain,
+			consensus_data_provider: Some(Box::new(consensus_data_provider)),
+			inherent_data_providers,
+		}
+	);
+
+	// the manual seal authorship task is considered essential, i.e. if it
+	// fails we take down the service with it.
+	task_manager.spawn_essential_handle().spawn_blocking("manual-seal", authorship_future);
+
+	network_starter.start_network();
+	Ok(task_manager)
+}
+
+/// Builds a new service for a light client. Light clients run no GRANDPA voter, but they
+/// still check the finality parameters so that a broken chain spec fails on every node.
+pub fn new_light(
+	mut config: Configuration,
+	finality_overrides: &FinalityOverrides,
+) -> Result<TaskManager, ServiceError> {
+	finality_params(&config, finality_overrides)?;
+
+	let (client, backend, keystore_container, mut task_manager, on_demand) =
 		sc_service::new_light_parts::<Block, RuntimeApi, Executor>(&config)?;
 
+	config.network.extra_sets.push(sc_finality_grandpa::grandpa_peers_set_config());
+
+	let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
 	let transaction_pool = Arc::new(sc_transaction_pool::BasicPool::new_light(
 		config.transaction_pool.clone(),
 		config.prometheus_registry(),
@@ -239,29 +428,29 @@ pub fn new_light(config: Configuration) -> Result<TaskManager, ServiceError> {
 		on_demand.clone(),
 	));
 
-	let grandpa_block_import = sc_finality_grandpa::light_block_import(
-		client.clone(), backend.clone(), &(client.clone() as Arc<_>),
-		Arc::new(on_demand.checker().clone()) as Arc<_>,
+	let (grandpa_block_import, _) = sc_finality_grandpa::block_import(
+		client.clone(), &(client.clone() as Arc<_>), select_chain.clone(),
 	)?;
-	let finality_proof_import = grandpa_block_import.clone();
-	let finality_proof_request_builder =
-		finality_proof_import.create_finality_proof_request_builder();
+	let justification_import = grandpa_block_import.clone();
 
-	let import_queue = sc_consensus_aura::import_queue::<_, _, _, AuraPair, _, _>(
-		sc_consensus_aura::slot_duration(&*client)?,
+	let (babe_block_import, babe_link) = sc_consensus_babe::block_import(
+		sc_consensus_babe::Config::get_or_compute(&*client)?,
 		grandpa_block_import,
-		None,
-		Some(Box::new(finality_proof_import)),
 		client.clone(),
+	)?;
+
+	let import_queue = sc_consensus_babe::import_queue(
+		babe_link,
+		babe_block_import,
+		Some(Box::new(justification_import)),
+		client.clone(),
+		select_chain,
 		InherentDataProviders::new(),
 		&task_manager.spawn_handle(),
 		config.prometheus_registry(),
 		sp_consensus::NeverCanAuthor,
 	)?;
 
-	let finality_proof_provider =
-		GrandpaFinalityProofProvider::new_for_service(backend.clone(), client.clone());
-
 	let (network, network_status_sinks, system_rpc_tx, network_starter) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
 			config: &config,
@@ -271,8 +460,6 @@ pub fn new_light(config: Configuration) -> Result<TaskManager, ServiceError> {
 			import_queue,
 			on_demand: Some(on_demand.clone()),
 			block_announce_validator_builder: None,
-			finality_proof_request_builder: Some(finality_proof_request_builder),
-			finality_proof_provider: Some(finality_proof_provider),
 		})?;
 
 	if config.offchain_worker.enabled {
@@ -287,10 +474,9 @@ pub fn new_light(config: Configuration) -> Result<TaskManager, ServiceError> {
 		task_manager: &mut task_manager,
 		on_demand: Some(on_demand),
 		rpc_extensions_builder: Box::new(|_, _| ()),
-		telemetry_connection_sinks: sc_service::TelemetryConnectionSinks::default(),
 		config,
 		client,
-		keystore,
+		keystore: keystore_container.sync_keystore(),
 		backend,
 		network,
 		network_status_sinks,
@@ -0,0 +1,601 @@
+//! The `snapshot` subcommands: write the full state of a block, with its header and
+//! justification, to a compressed and checksummed file, verify such a file offline, and seed a
+//! fresh database with it so that a new node starts at that block instead of syncing history.
+
+use std::{fs, io::{Read, Write}, path::{Path, PathBuf}, sync::Arc};
+use codec::{Encode, Decode};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use kvdb::KeyValueDB;
+use structopt::StructOpt;
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams, SubstrateCli};
+use sc_client_api::{
+	AuxStore, BlockBackend, StorageProvider,
+	backend::{Backend, BlockImportOperation, NewBlockState},
+};
+use sc_client_db::{DatabaseSettings, DatabaseSettingsSrc};
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	hashing::blake2_256,
+	storage::{ChildInfo, Storage, StorageChild, StorageKey, well_known_keys},
+};
+use sp_runtime::{Justification, generic::BlockId, traits::{BlakeTwo256, Block as BlockT, Hash, Header as HeaderT}};
+use sc_service::{Configuration, PartialComponents};
+use node_template_runtime::{EPOCH_DURATION_IN_SLOTS, opaque::Block};
+use crate::{cli::Cli, service};
+
+/// Identifies a snapshot file and the version of its format.
+const MAGIC: &[u8; 8] = b"poesnap2";
+
+type Header = <Block as BlockT>::Header;
+
+/// How many ancestors of the snapshot block to keep. BABE's epoch data refers to the blocks
+/// announcing the current and next epoch, which are at most two epochs of slots, and so at most
+/// as many blocks, behind the last finalized block.
+const ANCESTRY: u32 = 2 * EPOCH_DURATION_IN_SLOTS as u32;
+
+/// Auxiliary data of BABE and GRANDPA that a node needs to import the blocks after the snapshot.
+const AUX_KEYS: &[&[u8]] = &[
+	b"babe_epoch_changes_version",
+	b"babe_epoch_changes",
+	b"grandpa_schema_version",
+	b"grandpa_voters",
+	b"grandpa_completed_round",
+	b"grandpa_concluded_rounds",
+	b"grandpa_consensus_changes",
+];
+
+/// The BABE aux key of the chain weight up to a block.
+fn block_weight_key(hash: &<Block as BlockT>::Hash) -> Vec<u8> {
+	(b"block_weight", hash).encode()
+}
+
+/// Columns of the node's database, as laid out by `sc-client-db`.
+mod columns {
+	pub const NUM_COLUMNS: u32 = 11;
+	pub const META: u32 = 0;
+	pub const KEY_LOOKUP: u32 = 3;
+	pub const HEADER: u32 = 4;
+}
+
+/// The key `sc-client-db` stores a block under, the big-endian number followed by the hash.
+fn lookup_key(header: &Header) -> Vec<u8> {
+	let mut key = header.number().to_be_bytes().to_vec();
+	key.extend_from_slice(header.hash().as_ref());
+	key
+}
+
+/// Snapshot subcommands.
+#[derive(Debug, StructOpt)]
+pub enum SnapshotCmd {
+	/// Write the state of a block to a snapshot file.
+	Export(ExportSnapshotCmd),
+
+	/// Check a snapshot file's checksum and state root without a database.
+	Verify(VerifySnapshotCmd),
+
+	/// Seed a new database with a snapshot, making its block the best and finalized one.
+	Import(ImportSnapshotCmd),
+}
+
+/// The `snapshot export` command.
+#[derive(Debug, StructOpt)]
+pub struct ExportSnapshotCmd {
+	/// The file to write the snapshot to.
+	#[structopt(parse(from_os_str))]
+	pub output: PathBuf,
+
+	/// Block hash or number to snapshot. Defaults to the last finalized block.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub block: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// The `snapshot verify` command.
+#[derive(Debug, StructOpt)]
+pub struct VerifySnapshotCmd {
+	/// The snapshot file to verify.
+	#[structopt(parse(from_os_str))]
+	pub input: PathBuf,
+}
+
+/// The `snapshot import` command. The database must not exist yet, and the node must later run
+/// with the same pruning parameters as given here.
+#[derive(Debug, StructOpt)]
+pub struct ImportSnapshotCmd {
+	/// The snapshot file to import.
+	#[structopt(parse(from_os_str))]
+	pub input: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// The contents of a snapshot file, compressed after the magic and checksum.
+#[derive(Encode, Decode)]
+pub struct Snapshot {
+	/// The header of the snapshot block.
+	pub header: Header,
+	/// The justification finalizing the block, if the node kept one.
+	pub justification: Option<Justification>,
+	/// Every top-level key and value.
+	pub top: Vec<(Vec<u8>, Vec<u8>)>,
+	/// The keys and values of every default child trie. [storage key, pairs]
+	pub children: Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>,
+	/// The genesis header, which identifies the chain.
+	pub genesis: Header,
+	/// The headers of up to `ANCESTRY` ancestors of the block, parent first, not counting genesis.
+	pub ancestors: Vec<Header>,
+	/// The BABE and GRANDPA auxiliary data at the block. [key, value]
+	pub aux: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Snapshot {
+	/// Encode as `MAGIC`, the blake2-256 of the encoded snapshot, then the gzipped snapshot.
+	pub fn write_to(&self, path: &Path) -> sc_cli::Result<()> {
+		let encoded = self.encode();
+		let mut file = fs::File::create(path)?;
+		file.write_all(MAGIC)?;
+		file.write_all(&blake2_256(&encoded))?;
+		let mut encoder = GzEncoder::new(file, Compression::default());
+		encoder.write_all(&encoded)?;
+		encoder.finish()?;
+		Ok(())
+	}
+
+	/// Read a snapshot written by `write_to`, checking its magic and checksum.
+	pub fn read_from(path: &Path) -> sc_cli::Result<Self> {
+		let mut file = fs::File::open(path)?;
+		let mut magic = [0u8; 8];
+		let mut checksum = [0u8; 32];
+		file.read_exact(&mut magic)?;
+		if &magic != MAGIC {
+			return Err(format!("`{}` is not a snapshot file", path.display()).into());
+		}
+		file.read_exact(&mut checksum)?;
+
+		let mut encoded = Vec::new();
+		GzDecoder::new(file).read_to_end(&mut encoded)?;
+		if blake2_256(&encoded) != checksum {
+			return Err("Snapshot checksum mismatch, the file is corrupted".into());
+		}
+		Self::decode(&mut &encoded[..])
+			.map_err(|e| format!("Error decoding snapshot: {}", e).into())
+	}
+
+	/// Check that the state, including every child trie, hashes to the header's state root.
+	pub fn verify_state_root(&self) -> sc_cli::Result<()> {
+		for (storage_key, pairs) in &self.children {
+			let root = BlakeTwo256::trie_root(pairs.clone());
+			let stored = self.top.iter().find(|(key, _)| key == storage_key).map(|(_, value)| value);
+			if stored.map(|value| &value[..]) != Some(root.as_ref()) {
+				return Err(format!("Child trie root mismatch for 0x{}", hex::encode(storage_key)).into());
+			}
+		}
+
+		if BlakeTwo256::trie_root(self.top.clone()) != *self.header.state_root() {
+			return Err("Snapshot state does not match the header's state root".into());
+		}
+		Ok(())
+	}
+
+	/// Check that the ancestors are the block's parent, its grandparent and so on, and that the
+	/// last of them is a child of genesis if it is block 1.
+	pub fn verify_ancestry(&self) -> sc_cli::Result<()> {
+		if *self.genesis.number() != 0 {
+			return Err("Snapshot genesis header is not block 0".into());
+		}
+		if *self.header.number() > 1 && self.ancestors.is_empty() {
+			return Err("Snapshot lacks the parent of its block".into());
+		}
+		let mut child = &self.header;
+		for ancestor in &self.ancestors {
+			if *child.parent_hash() != ancestor.hash() || *ancestor.number() + 1 != *child.number() {
+				return Err(format!("Snapshot ancestor #{} is not the parent of #{}", ancestor.number(), child.number()).into());
+			}
+			child = ancestor;
+		}
+		if *child.number() == 1 && *child.parent_hash() != self.genesis.hash() {
+			return Err("Snapshot block #1 is not a child of its genesis".into());
+		}
+		Ok(())
+	}
+
+	/// Convert into `Storage`, moving each child trie out of the top-level keys.
+	pub fn into_storage(self) -> Storage {
+		let prefix = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX;
+		let top = self.top.into_iter().filter(|(key, _)| !key.starts_with(prefix)).collect();
+		let children_default = self.children.into_iter().map(|(storage_key, pairs)| {
+			let unprefixed = storage_key[prefix.len()..].to_vec();
+			let child = StorageChild {
+				data: pairs.into_iter().collect(),
+				child_info: ChildInfo::new_default(&unprefixed),
+			};
+			(unprefixed, child)
+		}).collect();
+		Storage { top, children_default }
+	}
+}
+
+macro_rules! impl_cli_configuration {
+	($($cmd:ty),*) => {$(
+		impl CliConfiguration for $cmd {
+			fn shared_params(&self) -> &SharedParams {
+				&self.shared_params
+			}
+
+			fn pruning_params(&self) -> Option<&PruningParams> {
+				Some(&self.pruning_params)
+			}
+
+			fn database_params(&self) -> Option<&DatabaseParams> {
+				Some(&self.database_params)
+			}
+		}
+	)*}
+}
+
+impl_cli_configuration!(ExportSnapshotCmd, ImportSnapshotCmd);
+
+impl SnapshotCmd {
+	/// Run the selected subcommand.
+	pub fn run(&self, cli: &Cli) -> sc_cli::Result<()> {
+		match self {
+			SnapshotCmd::Export(cmd) => {
+				let runner = cli.create_runner(cmd)?;
+				runner.async_run(|config| {
+					let PartialComponents { client, task_manager, .. } = service::new_partial(&config)?;
+					Ok((cmd.run(client), task_manager))
+				})
+			},
+			SnapshotCmd::Verify(cmd) => cmd.run(),
+			SnapshotCmd::Import(cmd) => {
+				let runner = cli.create_runner(cmd)?;
+				runner.sync_run(|config| cmd.run(&config))
+			},
+		}
+	}
+}
+
+impl ExportSnapshotCmd {
+	/// Read the selected block's header, justification and state from `client` into the file.
+	pub async fn run<BA, C>(&self, client: Arc<C>) -> sc_cli::Result<()> where
+		BA: Backend<Block>,
+		C: BlockBackend<Block> + HeaderBackend<Block> + StorageProvider<Block, BA> + AuxStore,
+	{
+		let id = match &self.block {
+			Some(block) => block.parse::<Block>()?,
+			None => BlockId::Hash(client.info().finalized_hash),
+		};
+		let header = client.header(id)?
+			.ok_or_else(|| format!("Block {} not found", id))?;
+		let justification = client.justification(&id)?;
+
+		let empty = StorageKey(Vec::new());
+		let top = client.storage_pairs(&id, &empty)?
+			.into_iter()
+			.map(|(key, value)| (key.0, value.0))
+			.collect::<Vec<_>>();
+
+		let mut children = Vec::new();
+		for (storage_key, _) in &top {
+			if !storage_key.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+				continue;
+			}
+			let child_info = ChildInfo::new_default(
+				&storage_key[well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..],
+			);
+			let mut pairs = Vec::new();
+			for key in client.child_storage_keys(&id, &child_info, &empty)? {
+				if let Some(value) = client.child_storage(&id, &child_info, &key)? {
+					pairs.push((key.0, value.0));
+				}
+			}
+			children.push((storage_key.clone(), pairs));
+		}
+
+		let genesis = client.header(BlockId::Number(0))?
+			.ok_or("Genesis header not found")?;
+		let mut ancestors = Vec::new();
+		let mut parent_hash = *header.parent_hash();
+		for _ in 0..header.number().saturating_sub(1).min(ANCESTRY) {
+			let parent = client.header(BlockId::Hash(parent_hash))?
+				.ok_or_else(|| format!("Ancestor {} not found", parent_hash))?;
+			parent_hash = *parent.parent_hash();
+			ancestors.push(parent);
+		}
+
+		let mut aux = Vec::new();
+		for key in AUX_KEYS.iter().map(|key| key.to_vec()).chain(Some(block_weight_key(&header.hash()))) {
+			if let Some(value) = client.get_aux(&key)? {
+				aux.push((key, value));
+			}
+		}
+
+		let snapshot = Snapshot { header, justification, top, children, genesis, ancestors, aux };
+		snapshot.write_to(&self.output)?;
+		println!(
+			"Exported the state of block #{} ({}){} to {}",
+			snapshot.header.number(),
+			snapshot.header.hash(),
+			if snapshot.justification.is_some() { " with its justification" } else { "" },
+			self.output.display(),
+		);
+		Ok(())
+	}
+}
+
+impl VerifySnapshotCmd {
+	/// Check the file and print the block it holds.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let snapshot = Snapshot::read_from(&self.input)?;
+		snapshot.verify_state_root()?;
+		snapshot.verify_ancestry()?;
+		println!(
+			"Snapshot of block #{} ({}) is valid: {} keys, {} child tries, {}justified",
+			snapshot.header.number(),
+			snapshot.header.hash(),
+			snapshot.top.len(),
+			snapshot.children.len(),
+			if snapshot.justification.is_some() { "" } else { "not " },
+		);
+		Ok(())
+	}
+}
+
+impl ImportSnapshotCmd {
+	/// Create the database configured for the chain and import the file into it.
+	pub fn run(&self, config: &Configuration) -> sc_cli::Result<()> {
+		let path = match &config.database {
+			DatabaseSettingsSrc::RocksDb { path, .. } => path,
+			_ => return Err("Snapshots can only be imported into a RocksDB database".into()),
+		};
+		if path.exists() {
+			return Err(format!("Database `{}` already exists, purge the chain first", path.display()).into());
+		}
+
+		let snapshot = Snapshot::read_from(&self.input)?;
+		let (number, hash) = (*snapshot.header.number(), snapshot.header.hash());
+		import_snapshot(snapshot, DatabaseSettings {
+			state_cache_size: config.state_cache_size,
+			state_cache_child_ratio: config.state_cache_child_ratio.map(|v| (v, 100)),
+			state_pruning: config.state_pruning.clone(),
+			source: config.database.clone(),
+		})?;
+		println!("Imported block #{} ({}) as the best and finalized block", number, hash);
+		Ok(())
+	}
+}
+
+/// Seed the new RocksDB database of `settings` with the snapshot.
+///
+/// The database only keeps the headers of the block's ancestors and genesis, without bodies or
+/// state, so it can serve neither blocks nor state before the snapshot. The node syncs on from
+/// the snapshot block, which becomes the best and finalized block.
+pub fn import_snapshot(snapshot: Snapshot, settings: DatabaseSettings) -> sc_cli::Result<()> {
+	snapshot.verify_state_root()?;
+	snapshot.verify_ancestry()?;
+	let path = match &settings.source {
+		DatabaseSettingsSrc::RocksDb { path, .. } => path.clone(),
+		_ => return Err("Snapshots can only be imported into a RocksDB database".into()),
+	};
+
+	// Let `sc-client-db` create the database with its columns, version and pruning mode.
+	drop(sc_client_db::new_db_backend::<Block>(DatabaseSettings {
+		state_cache_size: settings.state_cache_size,
+		state_cache_child_ratio: settings.state_cache_child_ratio,
+		state_pruning: settings.state_pruning.clone(),
+		source: settings.source.clone(),
+	})?);
+
+	// Index the ancestors and genesis the way `sc-client-db` does for imported blocks, and make
+	// the parent of the snapshot block the best and finalized block it is imported onto.
+	if *snapshot.header.number() > 0 {
+		let db = kvdb_rocksdb::Database::open(
+			&kvdb_rocksdb::DatabaseConfig::with_columns(columns::NUM_COLUMNS),
+			path.to_str().ok_or("Database path is not valid UTF-8")?,
+		)?;
+		let mut transaction = db.transaction();
+		for header in snapshot.ancestors.iter().chain(Some(&snapshot.genesis)) {
+			let lookup = lookup_key(header);
+			transaction.put(columns::KEY_LOOKUP, &header.number().to_be_bytes(), &lookup);
+			transaction.put(columns::KEY_LOOKUP, header.hash().as_ref(), &lookup);
+			transaction.put(columns::HEADER, &lookup, &header.encode());
+		}
+		let parent = snapshot.ancestors.first().unwrap_or(&snapshot.genesis);
+		transaction.put(columns::META, b"gen", snapshot.genesis.hash().as_ref());
+		transaction.put(columns::META, b"best", &lookup_key(parent));
+		transaction.put(columns::META, b"final", &lookup_key(parent));
+		db.write(transaction)?;
+	}
+
+	// Commit the snapshot block on the parent. The operation starts from empty state, so
+	// `reset_storage` writes the whole trie, and the state database treats the parent as
+	// canonicalized since it has not seen it.
+	let backend = sc_client_db::new_db_backend::<Block>(settings)?;
+	let (header, justification, aux) = (snapshot.header.clone(), snapshot.justification.clone(), snapshot.aux.clone());
+	let state_root = *header.state_root();
+	let mut op = backend.begin_operation()?;
+	if op.reset_storage(snapshot.into_storage())? != state_root {
+		return Err("Imported state does not match the header's state root".into());
+	}
+	op.set_block_data(header, None, justification, NewBlockState::Final)?;
+	op.insert_aux(aux.into_iter().map(|(key, value)| (key, Some(value))))?;
+	backend.commit_operation(op)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_client_api::backend::StateBackend;
+
+	/// A snapshot holding one child trie, with a header committing to its state.
+	fn snapshot() -> Snapshot {
+		let mut storage_key = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec();
+		storage_key.extend_from_slice(b"child");
+		let pairs = vec![(b"a".to_vec(), b"1".to_vec()), (b"b".to_vec(), b"2".to_vec())];
+		let child_root = BlakeTwo256::trie_root(pairs.clone());
+
+		let top = vec![
+			(storage_key.clone(), child_root.as_ref().to_vec()),
+			(b"key".to_vec(), b"value".to_vec()),
+		];
+		let genesis = header(0, Default::default(), Default::default());
+		let header = header(1, genesis.hash(), BlakeTwo256::trie_root(top.clone()));
+		Snapshot {
+			header,
+			justification: Some(vec![1, 2, 3]),
+			top,
+			children: vec![(storage_key, pairs)],
+			genesis,
+			ancestors: Vec::new(),
+			aux: vec![(b"babe_epoch_changes".to_vec(), vec![4, 5, 6])],
+		}
+	}
+
+	fn header(number: u32, parent_hash: <Block as BlockT>::Hash, state_root: <Block as BlockT>::Hash) -> Header {
+		HeaderT::new(number, Default::default(), state_root, parent_hash, Default::default())
+	}
+
+	/// The snapshot moved to block 3, on top of ancestors 2 and 1.
+	fn deep_snapshot() -> Snapshot {
+		let mut snapshot = snapshot();
+		let first = header(1, snapshot.genesis.hash(), Default::default());
+		let second = header(2, first.hash(), Default::default());
+		snapshot.header = header(3, second.hash(), *snapshot.header.state_root());
+		snapshot.ancestors = vec![second, first];
+		snapshot
+	}
+
+	/// A path in the temporary directory unique to this process and test.
+	fn temp_path(name: &str) -> PathBuf {
+		std::env::temp_dir().join(format!("poesnap-{}-{}.snap", std::process::id(), name))
+	}
+
+	#[test]
+	fn round_trip_keeps_snapshot() {
+		let path = temp_path("round-trip");
+		let snapshot = snapshot();
+		snapshot.verify_state_root().unwrap();
+		snapshot.write_to(&path).unwrap();
+
+		let read = Snapshot::read_from(&path).unwrap();
+		fs::remove_file(&path).unwrap();
+		assert_eq!(read.encode(), snapshot.encode());
+		read.verify_state_root().unwrap();
+	}
+
+	#[test]
+	fn corrupted_file_is_rejected() {
+		let path = temp_path("corrupted");
+		snapshot().write_to(&path).unwrap();
+		let written = fs::read(&path).unwrap();
+
+		// A flipped checksum byte.
+		let mut bytes = written.clone();
+		bytes[MAGIC.len()] ^= 1;
+		fs::write(&path, &bytes).unwrap();
+		assert!(Snapshot::read_from(&path).is_err());
+
+		// A flipped byte in the compressed snapshot.
+		let mut bytes = written.clone();
+		let last = bytes.len() - 1;
+		bytes[last] ^= 1;
+		fs::write(&path, &bytes).unwrap();
+		assert!(Snapshot::read_from(&path).is_err());
+
+		// A wrong magic.
+		let mut bytes = written;
+		bytes[0] ^= 1;
+		fs::write(&path, &bytes).unwrap();
+		assert!(Snapshot::read_from(&path).is_err());
+
+		fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn tampered_state_fails_verification() {
+		let mut tampered = snapshot();
+		tampered.top[1].1.push(0);
+		assert!(tampered.verify_state_root().is_err());
+
+		let mut tampered = snapshot();
+		tampered.children[0].1[0].1 = b"3".to_vec();
+		assert!(tampered.verify_state_root().is_err());
+	}
+
+	#[test]
+	fn broken_ancestry_fails_verification() {
+		snapshot().verify_ancestry().unwrap();
+		deep_snapshot().verify_ancestry().unwrap();
+
+		let mut orphan = snapshot();
+		orphan.genesis = header(0, Default::default(), BlakeTwo256::hash(b"other"));
+		assert!(orphan.verify_ancestry().is_err());
+
+		let mut gap = deep_snapshot();
+		gap.ancestors.remove(0);
+		assert!(gap.verify_ancestry().is_err());
+
+		let mut parentless = deep_snapshot();
+		parentless.ancestors.clear();
+		assert!(parentless.verify_ancestry().is_err());
+	}
+
+	#[test]
+	fn import_seeds_database_at_snapshot_block() {
+		let dir = tempfile::tempdir().unwrap();
+		let settings = || DatabaseSettings {
+			state_cache_size: 0,
+			state_cache_child_ratio: None,
+			state_pruning: sc_client_db::PruningMode::keep_blocks(256),
+			source: DatabaseSettingsSrc::RocksDb { path: dir.path().join("db"), cache_size: 16 },
+		};
+		let snapshot = deep_snapshot();
+		let (hash, genesis_hash) = (snapshot.header.hash(), snapshot.genesis.hash());
+		import_snapshot(snapshot, settings()).unwrap();
+
+		let backend = sc_client_db::new_db_backend::<Block>(settings()).unwrap();
+		let info = backend.blockchain().info();
+		assert_eq!((info.best_hash, info.best_number), (hash, 3));
+		assert_eq!((info.finalized_hash, info.finalized_number), (hash, 3));
+		assert_eq!(info.genesis_hash, genesis_hash);
+		assert_eq!(backend.blockchain().hash(2).unwrap(), Some(deep_snapshot().ancestors[0].hash()));
+
+		let state = backend.state_at(BlockId::Hash(hash)).unwrap();
+		assert_eq!(state.storage(b"key").unwrap(), Some(b"value".to_vec()));
+		assert_eq!(state.child_storage(&ChildInfo::new_default(b"child"), b"a").unwrap(), Some(b"1".to_vec()));
+		assert_eq!(backend.get_aux(b"babe_epoch_changes").unwrap(), Some(vec![4, 5, 6]));
+	}
+
+	#[test]
+	fn into_storage_separates_child_tries() {
+		let storage = snapshot().into_storage();
+		assert_eq!(storage.top.len(), 1);
+		assert_eq!(storage.top.get(&b"key".to_vec()), Some(&b"value".to_vec()));
+
+		let child = storage.children_default.get(&b"child".to_vec()).unwrap();
+		assert_eq!(child.data.get(&b"a".to_vec()), Some(&b"1".to_vec()));
+		assert_eq!(child.child_info, ChildInfo::new_default(b"child"));
+	}
+}
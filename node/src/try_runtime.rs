@@ -0,0 +1,94 @@
+//! The `try-runtime` subcommand: runs a runtime upgrade against a local state snapshot, without
+//! the network or a database, and reports the weight it used and the pallet checks that failed.
+
+use std::{fs, path::PathBuf};
+use codec::Decode;
+use structopt::StructOpt;
+use sc_executor::{NativeExecutor, WasmExecutionMethod};
+use sp_core::{
+	NeverNativeValue, hashing::blake2_256, storage::well_known_keys,
+	traits::{CodeExecutor, RuntimeCode, WrappedRuntimeCode},
+};
+use sp_runtime::BuildStorage;
+use try_runtime_api::UpgradeReport;
+use crate::{chain_spec::ChainSpec, service::Executor, snapshot::Snapshot};
+
+/// The `try-runtime` command used to check a runtime upgrade before submitting it.
+#[derive(Debug, StructOpt)]
+pub struct TryRuntimeCmd {
+	/// The state to upgrade: a chain spec written by `export-state`, or a `.snap` file written
+	/// by `snapshot export`.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub snapshot: PathBuf,
+
+	/// The wasm runtime to upgrade to, built with the `try-runtime` feature.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub wasm: PathBuf,
+}
+
+impl TryRuntimeCmd {
+	/// Run the command, failing if a check failed or the upgrade doesn't fit in a block.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let mut storage = if self.snapshot.extension().map_or(false, |ext| ext == "snap") {
+			let snapshot = Snapshot::read_from(&self.snapshot)?;
+			snapshot.verify_state_root()?;
+			snapshot.into_storage()
+		} else {
+			ChainSpec::from_json_file(self.snapshot.clone())?.build_storage()?
+		};
+		let code = fs::read(&self.wasm)
+			.map_err(|e| format!("Error reading `{}`: {}", self.wasm.display(), e))?;
+		storage.top.insert(well_known_keys::CODE.to_vec(), code.clone());
+
+		let report = execute_upgrade(storage, &code)?;
+		let used = report.weight as f64 / report.max_block_weight.max(1) as f64 * 100.0;
+		println!("Upgrade weight: {} ({:.2}% of a block)", report.weight, used);
+		for failure in &report.failures {
+			println!(
+				"{} {} failed: {}",
+				String::from_utf8_lossy(&failure.pallet),
+				if failure.pre_upgrade { "pre_upgrade" } else { "post_upgrade" },
+				String::from_utf8_lossy(&failure.message),
+			);
+		}
+
+		if !report.failures.is_empty() {
+			return Err(format!("{} upgrade check(s) failed", report.failures.len()).into());
+		}
+		if report.weight > report.max_block_weight {
+			return Err("The upgrade uses more weight than a block allows".into());
+		}
+		println!("All upgrade checks passed");
+		Ok(())
+	}
+}
+
+/// Call `TryRuntime_on_runtime_upgrade` of `code` on `storage`, always in wasm so the runtime
+/// under test is the one that runs.
+fn execute_upgrade(storage: sp_core::storage::Storage, code: &[u8]) -> sc_cli::Result<UpgradeReport> {
+	let executor = NativeExecutor::<Executor>::new(WasmExecutionMethod::Interpreted, None, 1);
+	let code_fetcher = WrappedRuntimeCode(code.into());
+	let runtime_code = RuntimeCode {
+		code_fetcher: &code_fetcher,
+		heap_pages: None,
+		hash: blake2_256(code).to_vec(),
+	};
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	let mut ext = ext.ext();
+	let (result, _) = executor.call::<NeverNativeValue, fn() -> Result<NeverNativeValue, String>>(
+		&mut ext,
+		&runtime_code,
+		"TryRuntime_on_runtime_upgrade",
+		&[],
+		false,
+		None,
+	);
+	let encoded = result
+		.map_err(|e| format!(
+			"Error running the upgrade: {}. Was the runtime built with the `try-runtime` feature?", e,
+		))?
+		.into_encoded();
+	UpgradeReport::decode(&mut &encoded[..])
+		.map_err(|e| format!("Error decoding the upgrade report: {}", e).into())
+}
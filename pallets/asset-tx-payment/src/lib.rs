@@ -0,0 +1,309 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Lets signed transactions pay their fee in a `pallet_assets` asset instead of the native
+/// currency. The fee is computed in native units as usual, converted at a rate set by root and
+/// paid to `FeeDestination`, which thereby takes the asset in exchange for the native fee. It
+/// pays that native fee through `OnChargeTransaction`, so block authors get their usual share.
+///
+/// An account paying only in assets holds no native balance, so it is never reaped, yet
+/// `CheckNonce` keeps its nonce in a `System::Account` entry. Each such account therefore costs a
+/// storage entry that no existential deposit pays for; a rate should only be set for assets whose
+/// minimum balance makes creating many payers too expensive to bloat the state with.
+
+use codec::{Encode, Decode};
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure, StorageMap,
+	dispatch::DispatchResult,
+	traits::{Get, UnfilteredDispatchable},
+	weights::{DispatchInfo, PostDispatchInfo},
+};
+use frame_system::ensure_root;
+use pallet_transaction_payment::{ChargeTransactionPayment, OnChargeTransaction};
+use sp_runtime::{
+	FixedPointNumber, FixedPointOperand, FixedU128, SaturatedConversion,
+	traits::{
+		SignedExtension, DispatchInfoOf, PostDispatchInfoOf, Dispatchable, StaticLookup, Zero, Saturating,
+	},
+	transaction_validity::{
+		TransactionValidity, TransactionValidityError, InvalidTransaction, ValidTransaction,
+		TransactionPriority,
+	},
+};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+type BalanceOf<T> =
+	<<T as pallet_transaction_payment::Config>::OnChargeTransaction as OnChargeTransaction<T>>::Balance;
+type LiquidityInfoOf<T> =
+	<<T as pallet_transaction_payment::Config>::OnChargeTransaction as OnChargeTransaction<T>>::LiquidityInfo;
+type AssetBalanceOf<T> = <T as pallet_assets::Config>::Balance;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: pallet_transaction_payment::Config + pallet_assets::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// The account receiving the fees paid in assets, e.g. the treasury.
+	type FeeDestination: Get<Self::AccountId>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as AssetTxPayment {
+		/// Units of an asset charged per unit of native fee. Assets without a rate can't pay fees.
+		ConversionRates get(fn conversion_rate): map hasher(twox_64_concat) T::AssetId => Option<FixedU128>;
+		/// The asset the transaction being dispatched pays its fee in, so that fees charged by
+		/// the call itself can be taken in the same asset. Cleared after dispatch.
+		FeeAssets get(fn fee_asset): map hasher(blake2_128_concat) T::AccountId => Option<T::AssetId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		AssetId = <T as pallet_assets::Config>::AssetId,
+		Balance = AssetBalanceOf<T>,
+	{
+		/// The conversion rate of an asset was set, or removed. [asset_id, rate]
+		ConversionRateSet(AssetId, Option<FixedU128>),
+		/// A transaction fee was paid in an asset, after refunds. [who, asset_id, amount]
+		AssetFeePaid(AccountId, AssetId, Balance),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The asset has no conversion rate, so it can't pay fees.
+		NoConversionRate,
+		/// The asset's conversion rate turns a non-zero fee into nothing.
+		ZeroAssetFee,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Set the number of `asset_id` units charged per unit of native fee, or stop accepting
+		/// the asset for fees with `None`. Must be called by root.
+		#[weight = 10_000 + T::DbWeight::get().writes(1)]
+		pub fn set_conversion_rate(origin, asset_id: T::AssetId, rate: Option<FixedU128>) {
+			ensure_root(origin)?;
+
+			match rate {
+				Some(rate) => ConversionRates::<T>::insert(asset_id, rate),
+				None => ConversionRates::<T>::remove(asset_id),
+			}
+
+			Self::deposit_event(RawEvent::ConversionRateSet(asset_id, rate));
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The amount of `asset_id` matching a native `fee`, rounded up so that fractions of an asset
+	/// unit are never free, if the asset can pay fees.
+	pub fn to_asset_fee(asset_id: T::AssetId, fee: BalanceOf<T>) -> Option<AssetBalanceOf<T>> {
+		ConversionRates::<T>::get(asset_id).map(|rate| {
+			let amount = FixedU128::saturating_from_integer(fee.saturated_into::<u128>())
+				.saturating_mul(rate)
+				.ceil();
+			(amount.into_inner() / FixedU128::accuracy()).saturated_into()
+		})
+	}
+
+	/// Charge `who` a `fee` given in native units in `asset_id`, paying it to `FeeDestination`
+	/// at the same rate as transaction fees.
+	pub fn charge_in_asset(who: &T::AccountId, asset_id: T::AssetId, fee: BalanceOf<T>) -> DispatchResult {
+		let amount = Self::to_asset_fee(asset_id, fee).ok_or(Error::<T>::NoConversionRate)?;
+		ensure!(!amount.is_zero() || fee.is_zero(), Error::<T>::ZeroAssetFee);
+		Self::transfer_asset(who.clone(), T::FeeDestination::get(), asset_id, amount)
+	}
+
+	/// Move `amount` of `asset_id` as if `from` had signed an asset transfer.
+	fn transfer_asset(
+		from: T::AccountId,
+		to: T::AccountId,
+		asset_id: T::AssetId,
+		amount: AssetBalanceOf<T>,
+	) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
+		pallet_assets::Call::<T>::transfer(asset_id, T::Lookup::unlookup(to), amount)
+			.dispatch_bypass_filter(frame_system::RawOrigin::Signed(from).into())
+			.map(|_| ())
+			.map_err(|e| e.error)
+	}
+}
+
+/// What a transaction was charged before dispatch, settled after it.
+pub enum InitialPayment<T: Config + Send + Sync> where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+{
+	/// Charged in the native currency by `ChargeTransactionPayment`.
+	Native(<ChargeTransactionPayment<T> as SignedExtension>::Pre),
+	/// Charged in an asset, with the native fee withdrawn from the fee destination.
+	/// [who, asset_id, amount, tip, native_fee]
+	Asset(T::AccountId, T::AssetId, AssetBalanceOf<T>, BalanceOf<T>, LiquidityInfoOf<T>),
+}
+
+impl<T: Config + Send + Sync> Default for InitialPayment<T> where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+{
+	fn default() -> Self {
+		InitialPayment::Native(Default::default())
+	}
+}
+
+/// Require the transactor to pay for themselves, in `asset_id` if it is given and in the native
+/// currency otherwise. Replaces `ChargeTransactionPayment` in the runtime's `SignedExtra`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeAssetTxPayment<T: Config> {
+	#[codec(compact)]
+	tip: BalanceOf<T>,
+	asset_id: Option<T::AssetId>,
+}
+
+impl<T: Config> ChargeAssetTxPayment<T> {
+	/// Pay `tip`, in native units, on top of the fee, in `asset_id` or the native currency.
+	pub fn from(tip: BalanceOf<T>, asset_id: Option<T::AssetId>) -> Self {
+		Self { tip, asset_id }
+	}
+
+	/// The fee in native units and its amount in `asset_id`.
+	fn asset_fee(
+		&self,
+		asset_id: T::AssetId,
+		info: &DispatchInfoOf<T::Call>,
+		len: usize,
+	) -> Result<(BalanceOf<T>, AssetBalanceOf<T>), TransactionValidityError> where
+		BalanceOf<T>: FixedPointOperand,
+		T::Call: Dispatchable<Info=DispatchInfo>,
+	{
+		let fee = pallet_transaction_payment::Module::<T>::compute_fee(len as u32, info, self.tip);
+		let asset_fee = Module::<T>::to_asset_fee(asset_id, fee).ok_or(InvalidTransaction::Payment)?;
+		// A zero rate would let the fee destination pay every fee for nothing.
+		if asset_fee.is_zero() && !fee.is_zero() {
+			return Err(InvalidTransaction::Payment.into());
+		}
+		Ok((fee, asset_fee))
+	}
+}
+
+impl<T: Config> sp_std::fmt::Debug for ChargeAssetTxPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeAssetTxPayment<{:?}, {:?}>", self.tip, self.asset_id)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for ChargeAssetTxPayment<T> where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+{
+	const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type Pre = InitialPayment<T>;
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		match self.asset_id {
+			None => ChargeTransactionPayment::<T>::from(self.tip).validate(who, call, info, len),
+			Some(asset_id) => {
+				let (fee, asset_fee) = self.asset_fee(asset_id, info, len)?;
+				if pallet_assets::Module::<T>::balance(asset_id, who.clone()) < asset_fee {
+					return Err(InvalidTransaction::Payment.into());
+				}
+				// Prioritize by the native fee, like native payments, whatever the asset's rate.
+				Ok(ValidTransaction {
+					priority: fee.saturated_into::<TransactionPriority>(),
+					..Default::default()
+				})
+			},
+		}
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		match self.asset_id {
+			None => ChargeTransactionPayment::<T>::from(self.tip)
+				.pre_dispatch(who, call, info, len)
+				.map(InitialPayment::Native),
+			Some(asset_id) => {
+				let (fee, asset_fee) = self.asset_fee(asset_id, info, len)?;
+				let dest = T::FeeDestination::get();
+				Module::<T>::transfer_asset(who.clone(), dest.clone(), asset_id, asset_fee)
+					.map_err(|_| InvalidTransaction::Payment)?;
+				// Having taken the asset, the fee destination pays the native fee like a native
+				// payment. If it lacks the native balance, authors go without their share.
+				let native_fee = <T as pallet_transaction_payment::Config>::OnChargeTransaction::withdraw_fee(
+					&dest, call, info, fee, self.tip,
+				).unwrap_or_default();
+				FeeAssets::<T>::insert(who, asset_id);
+				Ok(InitialPayment::Asset(who.clone(), asset_id, asset_fee, self.tip, native_fee))
+			},
+		}
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		match pre {
+			InitialPayment::Native(pre) =>
+				ChargeTransactionPayment::<T>::post_dispatch(pre, info, post_info, len, result),
+			InitialPayment::Asset(who, asset_id, paid, tip, native_fee) => {
+				FeeAssets::<T>::remove(&who);
+				let actual_fee = pallet_transaction_payment::Module::<T>::compute_actual_fee(
+					len as u32, info, post_info, tip,
+				);
+				let refund = Module::<T>::to_asset_fee(asset_id, actual_fee)
+					.map(|actual| paid.saturating_sub(actual))
+					.unwrap_or_else(Zero::zero);
+				// The fee destination received `paid` before dispatch, so it can always refund.
+				let refund = match Module::<T>::transfer_asset(T::FeeDestination::get(), who.clone(), asset_id, refund) {
+					Ok(()) => refund,
+					Err(_) => Zero::zero(),
+				};
+
+				<T as pallet_transaction_payment::Config>::OnChargeTransaction::correct_and_deposit_fee(
+					&T::FeeDestination::get(), info, post_info, actual_fee, tip, native_fee,
+				)?;
+
+				Module::<T>::deposit_event(RawEvent::AssetFeePaid(who, asset_id, paid - refund));
+				Ok(())
+			},
+		}
+	}
+}
@@ -0,0 +1,172 @@
+use crate::{ChargeAssetTxPayment, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::Get, weights::{DispatchInfo, PostDispatchInfo}};
+use sp_runtime::{DispatchError, FixedPointNumber, FixedU128, traits::{SignedExtension, Zero}};
+
+const CALL: &<Test as frame_system::Config>::Call =
+	&Call::Balances(pallet_balances::Call::transfer(2, 69));
+
+fn info(weight: u64) -> DispatchInfo {
+	DispatchInfo { weight, ..Default::default() }
+}
+
+fn post_info(actual_weight: u64) -> PostDispatchInfo {
+	PostDispatchInfo { actual_weight: Some(actual_weight), ..Default::default() }
+}
+
+/// Create asset 0 and mint 1000 units to account 2, which holds no native balance.
+fn issue_asset() {
+	assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 1));
+	assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 1000));
+	assert_eq!(Assets::balance(0, 2), 1000);
+}
+
+#[test]
+fn set_conversion_rate_requires_root() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			AssetTxPayment::set_conversion_rate(Origin::signed(1), 0, Some(FixedU128::from(2))),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(AssetTxPayment::set_conversion_rate(Origin::root(), 0, Some(FixedU128::from(2))));
+		assert_eq!(AssetTxPayment::conversion_rate(0), Some(FixedU128::from(2)));
+	});
+}
+
+#[test]
+fn fee_is_paid_and_refunded_in_asset() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		assert_ok!(AssetTxPayment::set_conversion_rate(Origin::root(), 0, Some(FixedU128::from(2))));
+
+		// A fee of 5 for the weight and 10 for the length, charged twice over in the asset.
+		let pre = ChargeAssetTxPayment::<Test>::from(0, Some(0))
+			.pre_dispatch(&2, CALL, &info(5), 10)
+			.unwrap();
+		assert_eq!(Assets::balance(0, 2), 1000 - 30);
+		assert_eq!(Assets::balance(0, FeeDestination::get()), 30);
+		assert_eq!(AssetTxPayment::fee_asset(2), Some(0));
+
+		// Only 2 of the 5 weight was used, so the difference is refunded.
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(pre, &info(5), &post_info(2), 10, &Ok(())));
+		assert_eq!(Assets::balance(0, 2), 1000 - 24);
+		assert_eq!(Assets::balance(0, FeeDestination::get()), 24);
+		assert_eq!(Balances::free_balance(2), 0);
+		assert_eq!(AssetTxPayment::fee_asset(2), None);
+
+		// The fee destination paid the native fee on, as if the transaction had paid natively.
+		assert_eq!(Balances::free_balance(FeeDestination::get()), 100 - 12);
+		assert_eq!(Balances::free_balance(AUTHOR), 12);
+	});
+}
+
+#[test]
+fn asset_payment_is_prioritized_by_native_fee() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		assert_ok!(AssetTxPayment::set_conversion_rate(Origin::root(), 0, Some(FixedU128::from(10))));
+
+		let native = ChargeAssetTxPayment::<Test>::from(0, None)
+			.validate(&1, CALL, &info(5), 10)
+			.unwrap();
+		let asset = ChargeAssetTxPayment::<Test>::from(0, Some(0))
+			.validate(&2, CALL, &info(5), 10)
+			.unwrap();
+		assert_eq!(asset.priority, 15);
+		assert_eq!(asset.priority, native.priority);
+	});
+}
+
+#[test]
+fn charge_in_asset_uses_conversion_rate() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		assert_noop!(
+			AssetTxPayment::charge_in_asset(&2, 0, 10),
+			crate::Error::<Test>::NoConversionRate
+		);
+
+		assert_ok!(AssetTxPayment::set_conversion_rate(Origin::root(), 0, Some(FixedU128::from(2))));
+		assert_ok!(AssetTxPayment::charge_in_asset(&2, 0, 10));
+		assert_eq!(Assets::balance(0, 2), 1000 - 20);
+		assert_eq!(Assets::balance(0, FeeDestination::get()), 20);
+	});
+}
+
+#[test]
+fn asset_without_rate_or_funds_is_rejected() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		assert!(ChargeAssetTxPayment::<Test>::from(0, Some(0))
+			.pre_dispatch(&2, CALL, &info(5), 10)
+			.is_err());
+
+		assert_ok!(AssetTxPayment::set_conversion_rate(Origin::root(), 0, Some(FixedU128::from(100))));
+		assert!(ChargeAssetTxPayment::<Test>::from(0, Some(0))
+			.validate(&2, CALL, &info(5), 10)
+			.is_err());
+		assert_eq!(Assets::balance(0, 2), 1000);
+	});
+}
+
+#[test]
+fn native_payment_is_unchanged() {
+	new_test_ext().execute_with(|| {
+		let pre = ChargeAssetTxPayment::<Test>::from(0, None)
+			.pre_dispatch(&1, CALL, &info(5), 10)
+			.unwrap();
+		assert_eq!(Balances::free_balance(1), 100 - 15);
+
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(pre, &info(5), &post_info(2), 10, &Ok(())));
+		assert_eq!(Balances::free_balance(1), 100 - 12);
+		assert_eq!(Balances::free_balance(AUTHOR), 12);
+	});
+}
+
+#[test]
+fn asset_fee_is_rounded_up() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		assert_ok!(AssetTxPayment::set_conversion_rate(
+			Origin::root(), 0, Some(FixedU128::saturating_from_rational(3, 2)),
+		));
+
+		// A fee of 15 is 22.5 units of the asset.
+		let pre = ChargeAssetTxPayment::<Test>::from(0, Some(0))
+			.pre_dispatch(&2, CALL, &info(5), 10)
+			.unwrap();
+		assert_eq!(Assets::balance(0, 2), 1000 - 23);
+
+		// The actual fee of 13 is 19.5 units, so 3 are refunded.
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(pre, &info(5), &post_info(3), 10, &Ok(())));
+		assert_eq!(Assets::balance(0, 2), 1000 - 20);
+
+		// A third of a unit still costs a whole one.
+		assert_ok!(AssetTxPayment::set_conversion_rate(
+			Origin::root(), 0, Some(FixedU128::saturating_from_rational(1, 3)),
+		));
+		assert_ok!(AssetTxPayment::charge_in_asset(&2, 0, 1));
+		assert_eq!(Assets::balance(0, 2), 1000 - 21);
+	});
+}
+
+#[test]
+fn zero_asset_fee_is_rejected() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		assert_ok!(AssetTxPayment::set_conversion_rate(Origin::root(), 0, Some(FixedU128::zero())));
+
+		assert!(ChargeAssetTxPayment::<Test>::from(0, Some(0))
+			.validate(&2, CALL, &info(5), 10)
+			.is_err());
+		assert!(ChargeAssetTxPayment::<Test>::from(0, Some(0))
+			.pre_dispatch(&2, CALL, &info(5), 10)
+			.is_err());
+		assert_noop!(
+			AssetTxPayment::charge_in_asset(&2, 0, 10),
+			crate::Error::<Test>::ZeroAssetFee
+		);
+		// Nothing is owed for a free call.
+		assert_ok!(AssetTxPayment::charge_in_asset(&2, 0, 0));
+		assert_eq!(Balances::free_balance(FeeDestination::get()), 100);
+	});
+}
@@ -0,0 +1,37 @@
+//! Runtime API definition for the proof of existence pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Encode, Decode};
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// A claim record together with what is known about its owner's on-chain identity.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ClaimDetails<AccountId, BlockNumber, Judgement> {
+	/// The owner of the claim.
+	pub owner: AccountId,
+	/// The block the claim was created or last transferred in.
+	pub block_number: BlockNumber,
+	/// The owner's display name, only set once a registrar judged the identity as
+	/// reasonable or known good.
+	pub display: Option<Vec<u8>>,
+	/// The judgements registrars gave the owner's identity. [registrar index, judgement]
+	pub judgements: Vec<(u32, Judgement)>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Queries over claim ownership and transfer approvals.
+	pub trait PoeApi<AccountId, BlockNumber, Judgement> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Judgement: Codec,
+	{
+		/// The claim record of `proof` with the owner's identity, if the proof is claimed.
+		fn get_claim(proof: Vec<u8>) -> Option<ClaimDetails<AccountId, BlockNumber, Judgement>>;
+		/// The account approved to transfer `proof`, if any.
+		fn get_approved(proof: Vec<u8>) -> Option<AccountId>;
+		/// Whether `operator` may transfer every claim held by `owner`.
+		fn is_approved_for_all(owner: AccountId, operator: AccountId) -> bool;
+	}
+}
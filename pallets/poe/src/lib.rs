@@ -5,24 +5,109 @@
 /// https://substrate.dev/docs/en/knowledgebase/runtime/frame
 
 use frame_support::{
-    decl_module, decl_storage, decl_event, decl_error, ensure, StorageMap,traits::{Get}
+    decl_module, decl_storage, decl_event, decl_error, ensure, StorageMap, StorageDoubleMap,
+    Parameter, RuntimeDebug, transactional,
+    dispatch::{DispatchError, DispatchResult},
+    weights::Weight,
+    traits::{
+        Get, Currency, ReservableCurrency, OnUnbalanced, WithdrawReason, ExistenceRequirement,
+        Randomness, Filter, IsType, schedule::{ScheduleNamed, DispatchTime, LOWEST_PRIORITY},
+    },
 };
+use codec::{Encode, Decode};
 
-use frame_system::ensure_signed;
+use frame_system::{ensure_signed, ensure_root};
+use sp_runtime::{Percent, traits::Zero};
 use sp_std::vec::Vec;
 
+type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+
 #[cfg(test)]
 mod mock;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "try-runtime")]
+mod try_runtime;
+
 /// Configure the pallet by specifying the parameters and types on which it depends.
-pub trait Trait: frame_system::Trait {
+pub trait Config: frame_system::Config {
 	/// Because this pallet emits events, it depends on the runtime's definition of an event.
-    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
     
     type MaxClaimLength: Get<u32>;
+
+	/// The currency in which claim fees and scheduling deposits are paid.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The fee charged for creating a claim, in native units.
+	type ClaimFee: Get<BalanceOf<Self>>;
+
+	/// Handler for the claim fees collected in the native currency, e.g. the treasury.
+	type OnClaimFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// How the claim fee is taken from its payer, e.g. `NativeClaimFee`.
+	type ChargeClaimFee: ChargeClaimFee<Self::AccountId, BalanceOf<Self>>;
+
+	/// Source of randomness for claim salts.
+	type Randomness: Randomness<Self::Hash>;
+
+	/// The runtime call type scheduled claim actions are wrapped in. It is the runtime's call
+	/// type, so that scheduled actions can be checked against its call filter.
+	type ScheduledCall: Parameter + From<Call<Self>> + IsType<<Self as frame_system::Config>::Call>;
+
+	/// The caller origin scheduled claim actions are dispatched with.
+	type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+
+	/// The scheduler running claim actions at a later block.
+	type Scheduler: ScheduleNamed<Self::BlockNumber, Self::ScheduledCall, Self::PalletsOrigin>;
+
+	/// The deposit reserved for every scheduled action, returned once it ran or was cancelled.
+	type ScheduleDeposit: Get<BalanceOf<Self>>;
+
+	/// The maximum number of actions pending on a single claim.
+	type MaxScheduledPerClaim: Get<u32>;
+}
+
+/// The weight of cancelling the most actions a claim can have pending when it moves or is
+/// revoked: the scheduler's lookup and agenda and the deposit of each.
+fn cancel_all_scheduled_weight<T: Config>() -> Weight {
+	T::DbWeight::get().reads_writes(3, 3)
+		.saturating_mul(T::MaxScheduledPerClaim::get() as Weight)
+		.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+}
+
+/// Takes the claim fee, given in native units, from the account creating a claim.
+pub trait ChargeClaimFee<AccountId, Balance> {
+	/// Charge `fee` to `who`.
+	fn charge(who: &AccountId, fee: Balance) -> DispatchResult;
+}
+
+/// Withdraws the claim fee in the native currency and hands it to `OnClaimFee`.
+pub struct NativeClaimFee<T>(sp_std::marker::PhantomData<T>);
+impl<T: Config> ChargeClaimFee<T::AccountId, BalanceOf<T>> for NativeClaimFee<T> {
+	fn charge(who: &T::AccountId, fee: BalanceOf<T>) -> DispatchResult {
+		let fee = T::Currency::withdraw(
+			who,
+			fee,
+			WithdrawReason::Fee.into(),
+			ExistenceRequirement::KeepAlive,
+		)?;
+		T::OnClaimFee::on_unbalanced(fee);
+		Ok(())
+	}
+}
+
+/// A claim action that can be scheduled for a future block.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum ScheduledAction<AccountId> {
+	/// Revoke the claim.
+	Revoke,
+	/// Transfer the claim to the given account.
+	Transfer(AccountId),
 }
 
 // The pallet's runtime storage items.
@@ -31,34 +116,108 @@ decl_storage! {
 	// A unique name is used to ensure that the pallet's storage items are isolated.
 	// This name may be updated, but each pallet in the runtime must use a unique name.
 	// ---------------------------------vvvvvvvvvvvvvv
-	trait Store for Module<T: Trait> as TemplateModule {
+	trait Store for Module<T: Config> as TemplateModule {
 		//Proof get(fn proofs): map hasher(blake2_128_concat) Vec<u8> => (T::AccountId, T::BlockNumber);	
 		Proofs: map hasher(blake2_128_concat) Vec<u8> => (T::AccountId, T::BlockNumber);
+		/// A random salt drawn when a claim is created, usable as a nonce for off-chain receipts.
+		ClaimSalts get(fn claim_salt): map hasher(blake2_128_concat) Vec<u8> => Option<T::Hash>;
+		/// The account approved to transfer a single claim, cleared whenever the claim moves.
+		Approvals get(fn approved): map hasher(blake2_128_concat) Vec<u8> => Option<T::AccountId>;
+		/// Operators allowed to transfer every claim of an owner. [owner, operator]
+		OperatorApprovals get(fn is_approved_for_all):
+			double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => bool;
+		/// The asking price of claims listed for sale, cleared whenever the claim moves.
+		Listings get(fn listing): map hasher(blake2_128_concat) Vec<u8> => Option<BalanceOf<T>>;
+		/// The account that created a claim and the royalty it receives on every resale.
+		Creators get(fn creator): map hasher(blake2_128_concat) Vec<u8> => Option<(T::AccountId, Percent)>;
+		/// Actions pending on a claim, each holding a deposit of the account that scheduled it.
+		/// [when, who]
+		ScheduledActions get(fn scheduled_actions):
+			map hasher(blake2_128_concat) Vec<u8> => Vec<(T::BlockNumber, T::AccountId)>;
+	}
+	add_extra_genesis {
+		/// Claims registered at genesis. [proof, owner]
+		config(claims): Vec<(Vec<u8>, T::AccountId)>;
+		build(|config: &GenesisConfig<T>| {
+			for (proof, owner) in &config.claims {
+				assert!(T::MaxClaimLength::get() >= proof.len() as u32, "Genesis claim is too long");
+				assert!(!Proofs::<T>::contains_key(proof), "Duplicate genesis claim");
+				Proofs::<T>::insert(proof, (owner, T::BlockNumber::default()));
+			}
+		});
 	}
 }
 
 // Pallets use events to inform users when important changes are made.
 // https://substrate.dev/docs/en/knowledgebase/runtime/events
 decl_event!(
-	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		BlockNumber = <T as frame_system::Config>::BlockNumber,
+		Balance = BalanceOf<T>,
+	{
 		 /// Event emitted when a proof has been claimed. [who, claim]
         ClaimCreated(AccountId, Vec<u8>),
         ClaimTransfered(AccountId, Vec<u8>),
 		/// Event emitted when a claim is revoked by the owner. [who, claim]
 		ClaimRevoked(AccountId, Vec<u8>),
+		/// Event emitted when an owner approves an account to transfer a claim. [owner, spender, claim]
+		ClaimApproved(AccountId, AccountId, Vec<u8>),
+		/// Event emitted when an operator is enabled or disabled for all claims of an owner.
+		/// [owner, operator, approved]
+		ApprovalForAll(AccountId, AccountId, bool),
+		/// Event emitted when an owner schedules an action on a claim. [who, claim, when]
+		ClaimActionScheduled(AccountId, Vec<u8>, BlockNumber),
+		/// Event emitted when a scheduled claim action was cancelled. [who, claim, when]
+		ScheduledActionCancelled(AccountId, Vec<u8>, BlockNumber),
+		/// Event emitted when a scheduled claim action ran. [who, claim]
+		ScheduledActionExecuted(AccountId, Vec<u8>),
+		/// Event emitted when a scheduled claim action could not run, e.g. because the claim
+		/// changed hands in the meantime. [who, claim, error]
+		ScheduledActionFailed(AccountId, Vec<u8>, DispatchError),
+		/// Event emitted when the creator of a claim sets its resale royalty. [creator, claim, royalty]
+		RoyaltySet(AccountId, Vec<u8>, Percent),
+		/// Event emitted when an owner lists a claim for sale. [owner, claim, price]
+		ClaimListed(AccountId, Vec<u8>, Balance),
+		/// Event emitted when an owner withdraws a claim from sale. [owner, claim]
+		ClaimUnlisted(AccountId, Vec<u8>),
+		/// Event emitted when a listed claim is bought. [seller, buyer, claim, price]
+		ClaimSold(AccountId, AccountId, Vec<u8>, Balance),
 	}
 );
 
 // Errors inform users that something went wrong.
 decl_error! {
-	pub enum Error for Module<T: Trait> {
+	pub enum Error for Module<T: Config> {
 		/// The proof has already been claimed.
 		ProofAlreadyClaimed,
 		/// The proof does not exist, so it cannot be revoked.
 		NoSuchProof,
 		/// The proof is claimed by another account, so caller can't revoke it.
         NotProofOwner,
-        ProofTooLong
+        ProofTooLong,
+		/// The caller is neither the owner, the approved account nor an operator of the claim.
+		NotApprovedOrOwner,
+		/// The owner can't approve itself as spender or operator.
+		ApproveToCaller,
+		/// Claim actions can only be scheduled for a future block.
+		ScheduleInPast,
+		/// The same action is already scheduled for this claim and block.
+		AlreadyScheduled,
+		/// No action of the caller is scheduled for this claim and block.
+		NotScheduled,
+		/// Only the creator of a claim, while still owning it, can set its royalty.
+		NotCreator,
+		/// The claim is not listed for sale.
+		NotListed,
+		/// The asking price is above the buyer's maximum price.
+		PriceTooHigh,
+		/// The owner can't buy its own claim.
+		BuyOwnClaim,
+		/// The claim already has `MaxScheduledPerClaim` pending actions.
+		TooManyScheduled,
+		/// The runtime's call filter currently rejects the action, e.g. because it is paused.
+		ActionFiltered,
 	}
 }
 
@@ -66,14 +225,14 @@ decl_error! {
 // These functions materialize as "extrinsics", which are often compared to transactions.
 // Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 decl_module! {
-	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
 		// Errors must be initialized if they are used by the pallet.
 		type Error = Error<T>;
 
 		// Events must be initialized if they are used by the pallet.
 		fn deposit_event() = default;
 
-		#[weight = 10_001]
+		#[weight = 10_001 + T::DbWeight::get().reads_writes(6, 6)]
         fn create_claim(origin, proof: Vec<u8>) {
             // Check that the extrinsic was signed and get the signer.
             // This function will return an error if the extrinsic is not signed.
@@ -85,17 +244,24 @@ decl_module! {
 
             ensure!(T::MaxClaimLength::get() >= proof.len() as u32, Error::<T>::ProofTooLong);
 
+            // Charge the claim fee, possibly in the asset the transaction fee is paid in.
+            T::ChargeClaimFee::charge(&sender, T::ClaimFee::get())?;
+
             // Get the block number from the FRAME System module.
             let current_block = <frame_system::Module<T>>::block_number();
 
             // Store the proof with the sender and block number.
             Proofs::<T>::insert(&proof, (&sender, current_block));
+            Creators::<T>::insert(&proof, (&sender, Percent::zero()));
+
+            let salt = T::Randomness::random(&(b"poe/salt", &proof, &sender).encode());
+            ClaimSalts::<T>::insert(&proof, salt);
 
             // Emit an event that the claim was created.
             Self::deposit_event(RawEvent::ClaimCreated(sender, proof));
         }
 
-        #[weight = 10_000]
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 5) + cancel_all_scheduled_weight::<T>()]
         fn revoke_claim(origin, proof: Vec<u8>) {
             // Check that the extrinsic was signed and get the signer.
             // This function will return an error if the extrinsic is not signed.
@@ -112,26 +278,323 @@ decl_module! {
             ensure!(sender == owner, Error::<T>::NotProofOwner);
 
             //Remove claim from storage.
-            Proofs::<T>::remove(&proof);
+            Self::do_revoke(&proof);
 
             //Emit an event that the claim was erased.
             Self::deposit_event(RawEvent::ClaimRevoked(sender, proof));
         }
 
-        #[weight = 10_002]
+        #[weight = 10_002 + T::DbWeight::get().reads_writes(1, 3) + cancel_all_scheduled_weight::<T>()]
         fn transfer_claim(origin,  to: T::AccountId,  claim: Vec<u8>) {
             let sender = ensure_signed(origin)?;
 
-            let current_block = <frame_system::Module<T>>::block_number();
-       
+            ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::NoSuchProof);
+
             let (owner, _) = Proofs::<T>::get(&claim);
             ensure!(sender == owner, Error::<T>::NotProofOwner);
 
-            Proofs::<T>::remove(&claim);
-            Proofs::<T>::insert(&claim, (&to, current_block));
-                   
+            Self::do_transfer(&claim, &to);
+
             Self::deposit_event(RawEvent::ClaimTransfered(sender, claim));
         }
 
+		/// Approve `spender` to transfer a single claim on behalf of its owner. Only one account
+		/// can be approved per claim; the approval is cleared when the claim is transferred.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn approve(origin, proof: Vec<u8>, spender: T::AccountId) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
+
+			let (owner, _) = Proofs::<T>::get(&proof);
+			ensure!(sender == owner, Error::<T>::NotProofOwner);
+			ensure!(spender != owner, Error::<T>::ApproveToCaller);
+
+			Approvals::<T>::insert(&proof, &spender);
+
+			Self::deposit_event(RawEvent::ClaimApproved(owner, spender, proof));
+		}
+
+		/// Enable or disable `operator` to transfer every claim owned by the caller.
+		#[weight = 10_000 + T::DbWeight::get().writes(1)]
+		fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(sender != operator, Error::<T>::ApproveToCaller);
+
+			if approved {
+				OperatorApprovals::<T>::insert(&sender, &operator, true);
+			} else {
+				OperatorApprovals::<T>::remove(&sender, &operator);
+			}
+
+			Self::deposit_event(RawEvent::ApprovalForAll(sender, operator, approved));
+		}
+
+		/// Transfer a claim on behalf of its owner. The caller must be the owner, the account
+		/// approved for this claim or an operator of the owner.
+		#[weight = 10_002 + T::DbWeight::get().reads_writes(3, 3) + cancel_all_scheduled_weight::<T>()]
+		fn transfer_from(origin, proof: Vec<u8>, to: T::AccountId) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
+
+			let (owner, _) = Proofs::<T>::get(&proof);
+			ensure!(Self::is_approved_or_owner(&sender, &owner, &proof), Error::<T>::NotApprovedOrOwner);
+
+			Self::do_transfer(&proof, &to);
+
+			Self::deposit_event(RawEvent::ClaimTransfered(owner, proof));
+		}
+
+		/// Schedule revoking or transferring one of the caller's claims at block `when`. The
+		/// action is cancelled if the claim moves or is revoked before then. `ScheduleDeposit`
+		/// is reserved until the action ran or was cancelled.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(4, 4)]
+		#[transactional]
+		fn schedule_action(origin, proof: Vec<u8>, action: ScheduledAction<T::AccountId>, when: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
+
+			let (owner, _) = Proofs::<T>::get(&proof);
+			ensure!(sender == owner, Error::<T>::NotProofOwner);
+			ensure!(when > <frame_system::Module<T>>::block_number(), Error::<T>::ScheduleInPast);
+
+			let mut pending = ScheduledActions::<T>::get(&proof);
+			ensure!((pending.len() as u32) < T::MaxScheduledPerClaim::get(), Error::<T>::TooManyScheduled);
+
+			T::Currency::reserve(&sender, T::ScheduleDeposit::get())?;
+			T::Scheduler::schedule_named(
+				Self::schedule_id(&sender, &proof, when),
+				DispatchTime::At(when),
+				None,
+				LOWEST_PRIORITY,
+				frame_system::RawOrigin::Root.into(),
+				Call::<T>::run_scheduled(sender.clone(), proof.clone(), action, when).into(),
+			).map_err(|_| Error::<T>::AlreadyScheduled)?;
+
+			pending.push((when, sender.clone()));
+			ScheduledActions::<T>::insert(&proof, pending);
+
+			Self::deposit_event(RawEvent::ClaimActionScheduled(sender, proof, when));
+		}
+
+		/// Cancel an action the caller scheduled on a claim for block `when` and return its
+		/// deposit.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(4, 4)]
+		fn cancel_scheduled_action(origin, proof: Vec<u8>, when: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			T::Scheduler::cancel_named(Self::schedule_id(&sender, &proof, when))
+				.map_err(|_| Error::<T>::NotScheduled)?;
+			Self::release_scheduled(&sender, &proof, when);
+
+			Self::deposit_event(RawEvent::ScheduledActionCancelled(sender, proof, when));
+		}
+
+		/// Set the share of the price paid to the creator of a claim whenever it is resold. Only
+		/// the creator can set it, and only while still owning the claim, so buyers see the
+		/// royalty before they buy.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(2, 1)]
+		fn set_royalty(origin, proof: Vec<u8>, royalty: Percent) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
+
+			let (owner, _) = Proofs::<T>::get(&proof);
+			let creator = Creators::<T>::get(&proof).map(|(creator, _)| creator);
+			ensure!(sender == owner && Some(&sender) == creator.as_ref(), Error::<T>::NotCreator);
+
+			Creators::<T>::insert(&proof, (&sender, royalty));
+
+			Self::deposit_event(RawEvent::RoyaltySet(sender, proof, royalty));
+		}
+
+		/// List one of the caller's claims for sale at `price`, replacing any earlier price.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn list_claim(origin, proof: Vec<u8>, price: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
+
+			let (owner, _) = Proofs::<T>::get(&proof);
+			ensure!(sender == owner, Error::<T>::NotProofOwner);
+
+			Listings::<T>::insert(&proof, price);
+
+			Self::deposit_event(RawEvent::ClaimListed(sender, proof, price));
+		}
+
+		/// Withdraw one of the caller's claims from sale.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(2, 1)]
+		fn unlist_claim(origin, proof: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Listings::<T>::contains_key(&proof), Error::<T>::NotListed);
+
+			let (owner, _) = Proofs::<T>::get(&proof);
+			ensure!(sender == owner, Error::<T>::NotProofOwner);
+
+			Listings::<T>::remove(&proof);
+
+			Self::deposit_event(RawEvent::ClaimUnlisted(sender, proof));
+		}
+
+		/// Buy a listed claim for its asking price, as long as that is at most `max_price`. The
+		/// creator's royalty, if any, is paid out of the price and the seller receives the rest.
+		/// A royalty owed to a reaped creator account that is too small to recreate it is skipped.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(4, 6) + cancel_all_scheduled_weight::<T>()]
+		#[transactional]
+		fn buy_claim(origin, proof: Vec<u8>, max_price: BalanceOf<T>) {
+			let buyer = ensure_signed(origin)?;
+
+			let price = Listings::<T>::get(&proof).ok_or(Error::<T>::NotListed)?;
+			ensure!(price <= max_price, Error::<T>::PriceTooHigh);
+
+			let (seller, _) = Proofs::<T>::get(&proof);
+			ensure!(buyer != seller, Error::<T>::BuyOwnClaim);
+
+			let royalty = match Creators::<T>::get(&proof) {
+				Some((creator, royalty)) if creator != seller && !royalty.is_zero() => {
+					let amount = royalty * price;
+					// A royalty below the existential deposit can't recreate a reaped creator
+					// account. It goes to the seller instead of blocking every resale.
+					let creator_reaped = T::Currency::total_balance(&creator).is_zero();
+					if creator_reaped && amount < T::Currency::minimum_balance() {
+						Zero::zero()
+					} else {
+						T::Currency::transfer(&buyer, &creator, amount, ExistenceRequirement::KeepAlive)?;
+						amount
+					}
+				},
+				_ => Zero::zero(),
+			};
+			T::Currency::transfer(&buyer, &seller, price - royalty, ExistenceRequirement::KeepAlive)?;
+
+			Self::do_transfer(&proof, &buyer);
+
+			Self::deposit_event(RawEvent::ClaimSold(seller, buyer, proof, price));
+		}
+
+		/// Run a claim action `who` scheduled for block `when` and return its deposit. The owner
+		/// check and the runtime's call filter are applied again, since the scheduler dispatches
+		/// as root. Only dispatched by the scheduler.
+		#[weight = 10_002 + T::DbWeight::get().reads_writes(3, 5) + cancel_all_scheduled_weight::<T>()]
+		fn run_scheduled(
+			origin,
+			who: T::AccountId,
+			proof: Vec<u8>,
+			action: ScheduledAction<T::AccountId>,
+			when: T::BlockNumber,
+		) {
+			ensure_root(origin)?;
+
+			Self::release_scheduled(&who, &proof, when);
+			match Self::do_scheduled(&who, &proof, action) {
+				Ok(()) => Self::deposit_event(RawEvent::ScheduledActionExecuted(who, proof)),
+				Err(e) => Self::deposit_event(RawEvent::ScheduledActionFailed(who, proof, e)),
+			}
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The owner of a claim and the block it was created or last transferred in.
+	pub fn claim(proof: &[u8]) -> Option<(T::AccountId, T::BlockNumber)> {
+		if Proofs::<T>::contains_key(proof) {
+			Some(Proofs::<T>::get(proof))
+		} else {
+			None
+		}
+	}
+
+	/// Whether `who` may move a claim held by `owner`.
+	pub fn is_approved_or_owner(who: &T::AccountId, owner: &T::AccountId, proof: &[u8]) -> bool {
+		who == owner
+			|| Approvals::<T>::get(proof).as_ref() == Some(who)
+			|| OperatorApprovals::<T>::get(owner, who)
+	}
+
+	/// The scheduler name of an action `who` scheduled on `proof` for block `when`.
+	fn schedule_id(who: &T::AccountId, proof: &[u8], when: T::BlockNumber) -> Vec<u8> {
+		(b"poe/schedule", who, proof, when).encode()
+	}
+
+	/// Forget the action `who` scheduled on `proof` for block `when` and unreserve its deposit.
+	fn release_scheduled(who: &T::AccountId, proof: &[u8], when: T::BlockNumber) {
+		let mut pending = ScheduledActions::<T>::get(proof);
+		let len = pending.len();
+		pending.retain(|(at, account)| !(at == &when && account == who));
+		if pending.len() == len {
+			return;
+		}
+
+		if pending.is_empty() {
+			ScheduledActions::<T>::remove(proof);
+		} else {
+			ScheduledActions::<T>::insert(proof, pending);
+		}
+		T::Currency::unreserve(who, T::ScheduleDeposit::get());
+	}
+
+	/// Perform a scheduled action if `who` still owns the claim and the call the owner would
+	/// make for it passes the runtime's call filter.
+	fn do_scheduled(who: &T::AccountId, proof: &[u8], action: ScheduledAction<T::AccountId>) -> DispatchResult {
+		ensure!(Proofs::<T>::contains_key(proof), Error::<T>::NoSuchProof);
+
+		let (owner, _) = Proofs::<T>::get(proof);
+		ensure!(who == &owner, Error::<T>::NotProofOwner);
+
+		let call = match &action {
+			ScheduledAction::Revoke => Call::<T>::revoke_claim(proof.to_vec()),
+			ScheduledAction::Transfer(to) => Call::<T>::transfer_claim(to.clone(), proof.to_vec()),
+		};
+		let call: <T as frame_system::Config>::Call = T::ScheduledCall::from(call).into();
+		ensure!(<T as frame_system::Config>::BaseCallFilter::filter(&call), Error::<T>::ActionFiltered);
+
+		match action {
+			ScheduledAction::Revoke => {
+				Self::do_revoke(proof);
+				Self::deposit_event(RawEvent::ClaimRevoked(owner, proof.to_vec()));
+			},
+			ScheduledAction::Transfer(to) => {
+				Self::do_transfer(proof, &to);
+				Self::deposit_event(RawEvent::ClaimTransfered(owner, proof.to_vec()));
+			},
+		}
+		Ok(())
+	}
+
+	/// Remove a claim with its approval, listing, creator, salt and pending actions.
+	fn do_revoke(proof: &[u8]) {
+		Proofs::<T>::remove(proof);
+		Approvals::<T>::remove(proof);
+		Listings::<T>::remove(proof);
+		Creators::<T>::remove(proof);
+		ClaimSalts::<T>::remove(proof);
+		Self::cancel_all_scheduled(proof);
+	}
+
+	/// Move a claim to `to`, restarting its block number and clearing any single-claim approval,
+	/// listing and pending actions.
+	fn do_transfer(proof: &[u8], to: &T::AccountId) {
+		let current_block = <frame_system::Module<T>>::block_number();
+
+		Approvals::<T>::remove(proof);
+		Listings::<T>::remove(proof);
+		Proofs::<T>::insert(proof, (to, current_block));
+		Self::cancel_all_scheduled(proof);
+	}
+
+	/// Cancel every action pending on a claim and unreserve their deposits. Actions scheduled by
+	/// a former owner could never run, so they would only hold deposits and scheduler slots.
+	fn cancel_all_scheduled(proof: &[u8]) {
+		for (when, who) in ScheduledActions::<T>::take(proof) {
+			// An action due in this block may already be taken from the scheduler's agenda.
+			let _ = T::Scheduler::cancel_named(Self::schedule_id(&who, proof, when));
+			T::Currency::unreserve(&who, T::ScheduleDeposit::get());
+			Self::deposit_event(RawEvent::ScheduledActionCancelled(who, proof.to_vec(), when));
+		}
 	}
 }
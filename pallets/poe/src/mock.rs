@@ -1,30 +1,48 @@
-use crate::{Module, Trait};
+use crate as pallet_poe;
+use crate::Config;
+use std::cell::RefCell;
 use sp_core::H256;
-use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use frame_support::{
+	parameter_types,
+	traits::{Get, Randomness, Filter, schedule::{ScheduleNamed, DispatchTime, Period, Priority}},
+};
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
+	traits::{BlakeTwo256, IdentityLookup, Hash}, testing::Header,
 };
 use frame_system as system;
 
-impl_outer_origin! {
-	pub enum Origin for Test {}
-}
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
 
 // Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		PoeModule: pallet_poe::{Module, Call, Storage, Config<T>},
+	}
+);
 
-#[derive(Clone, Eq, PartialEq)]
-pub struct Test;
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
-	pub const MaximumBlockWeight: Weight = 1024;
-	pub const MaximumBlockLength: u32 = 2 * 1024;
-	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	pub const MaxClaimLength: u32 = 8;
+	pub const MaxLocks: u32 = 50;
+	pub const ClaimFee: u64 = 10;
+	pub const ScheduleDeposit: u64 = 5;
+	pub const MaxScheduledPerClaim: u32 = 2;
 }
 
-impl system::Trait for Test {
-	type BaseCallFilter = ();
+impl system::Config for Test {
+	type BaseCallFilter = TestFilter;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
 	type Origin = Origin;
-	type Call = ();
+	type Call = Call;
 	type Index = u64;
 	type BlockNumber = u64;
 	type Hash = H256;
@@ -34,28 +52,134 @@ impl system::Trait for Test {
 	type Header = Header;
 	type Event = ();
 	type BlockHashCount = BlockHashCount;
-	type MaximumBlockWeight = MaximumBlockWeight;
-	type DbWeight = ();
-	type BlockExecutionWeight = ();
-	type ExtrinsicBaseWeight = ();
-	type MaximumExtrinsicWeight = MaximumBlockWeight;
-	type MaximumBlockLength = MaximumBlockLength;
-	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
-	type PalletInfo = ();
-	type AccountData = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
+	type SS58Prefix = ();
 }
 
-impl Trait for Test {
+impl pallet_balances::Config for Test {
+	type MaxLocks = MaxLocks;
+	type Balance = u64;
 	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl Config for Test {
+	type Event = ();
+	type MaxClaimLength = MaxClaimLength;
+	type Currency = Balances;
+	type ClaimFee = ClaimFee;
+	type OnClaimFee = ();
+	type ChargeClaimFee = crate::NativeClaimFee<Test>;
+	type Randomness = TestRandomness;
+	type ScheduledCall = Call;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = TestScheduler;
+	type ScheduleDeposit = ScheduleDeposit;
+	type MaxScheduledPerClaim = MaxScheduledPerClaim;
+}
+
+thread_local! {
+	/// The existential deposit returned by `ExistentialDeposit`.
+	pub static EXISTENTIAL_DEPOSIT: RefCell<u64> = RefCell::new(1);
+
+	/// Whether `TestFilter` rejects `transfer_claim`.
+	pub static TRANSFERS_PAUSED: RefCell<bool> = RefCell::new(false);
+
+	/// Calls handed to `TestScheduler`. [name, block, call]
+	pub static SCHEDULED: RefCell<Vec<(Vec<u8>, u64, Call)>> = RefCell::new(Vec::new());
 }
 
-pub type TemplateModule = Module<Test>;
+/// An existential deposit that tests can raise.
+pub struct ExistentialDeposit;
+impl Get<u64> for ExistentialDeposit {
+	fn get() -> u64 {
+		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
+	}
+}
+
+/// Rejects `transfer_claim` while `TRANSFERS_PAUSED` is set, like a paused call in the runtime.
+pub struct TestFilter;
+impl Filter<Call> for TestFilter {
+	fn filter(call: &Call) -> bool {
+		match call {
+			Call::PoeModule(pallet_poe::Call::transfer_claim(..)) => !TRANSFERS_PAUSED.with(|p| *p.borrow()),
+			_ => true,
+		}
+	}
+}
+
+/// Records scheduled calls so that tests can run them when their block comes.
+pub struct TestScheduler;
+impl ScheduleNamed<u64, Call, OriginCaller> for TestScheduler {
+	type Address = u64;
+
+	fn schedule_named(
+		id: Vec<u8>,
+		when: DispatchTime<u64>,
+		_maybe_periodic: Option<Period<u64>>,
+		_priority: Priority,
+		_origin: OriginCaller,
+		call: Call,
+	) -> Result<u64, ()> {
+		let when = match when {
+			DispatchTime::At(n) => n,
+			DispatchTime::After(n) => System::block_number() + n + 1,
+		};
+		SCHEDULED.with(|s| {
+			if s.borrow().iter().any(|(name, _, _)| name == &id) {
+				return Err(());
+			}
+			s.borrow_mut().push((id, when, call));
+			Ok(when)
+		})
+	}
+
+	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
+		SCHEDULED.with(|s| {
+			let mut scheduled = s.borrow_mut();
+			let len = scheduled.len();
+			scheduled.retain(|(name, _, _)| name != &id);
+			if scheduled.len() < len { Ok(()) } else { Err(()) }
+		})
+	}
+}
+
+/// Take the calls scheduled for block `n` or earlier.
+pub fn take_scheduled(n: u64) -> Vec<Call> {
+	SCHEDULED.with(|s| {
+		let (due, pending): (Vec<_>, Vec<_>) = s.borrow_mut().drain(..).partition(|(_, when, _)| *when <= n);
+		*s.borrow_mut() = pending;
+		due.into_iter().map(|(_, _, call)| call).collect()
+	})
+}
+
+/// Deterministic randomness that only depends on the subject.
+pub struct TestRandomness;
+impl Randomness<H256> for TestRandomness {
+	fn random(subject: &[u8]) -> H256 {
+		BlakeTwo256::hash(subject)
+	}
+}
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	SCHEDULED.with(|s| s.borrow_mut().clear());
+	TRANSFERS_PAUSED.with(|p| *p.borrow_mut() = false);
+	EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = 1);
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 100), (2, 100), (3, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	crate::GenesisConfig::<Test> {
+		claims: vec![(vec![9, 9], 3)],
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
 }
@@ -1,23 +1,422 @@
-use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
-
-// #[test]
-// fn it_works_for_default_value() {
-// 	new_test_ext().execute_with(|| {
-// 		// Dispatch a signed extrinsic.
-// 		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
-// 		// Read pallet storage and assert an expected result.
-// 		assert_eq!(TemplateModule::something(), Some(42));
-// 	});
-// }
-
-// #[test]
-// fn correct_error_for_none_value() {
-// 	new_test_ext().execute_with(|| {
-// 		// Ensure the expected error is thrown when no value is present.
-// 		assert_noop!(
-// 			TemplateModule::cause_error(Origin::signed(1)),
-// 			Error::<Test>::NoneValue
-// 		);
-// 	});
-// }
+use crate::{Error, Proofs, ScheduledAction, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::{Currency, UnfilteredDispatchable}};
+use sp_runtime::{DispatchError, Percent};
+
+#[test]
+fn create_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_eq!(Proofs::<Test>::get(&claim), (1, 0));
+		assert_eq!(Balances::free_balance(1), 90);
+		assert!(PoeModule::claim_salt(&claim).is_some());
+	});
+}
+
+#[test]
+fn create_claim_fails_without_funds_for_fee() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(4), vec![0, 1]),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn create_claim_fails_when_too_long() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), vec![0; 9]),
+			Error::<Test>::ProofTooLong
+		);
+	});
+}
+
+#[test]
+fn approved_account_can_transfer_once() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::approve(Origin::signed(1), claim.clone(), 2));
+		assert_eq!(PoeModule::approved(&claim), Some(2));
+
+		assert_ok!(PoeModule::transfer_from(Origin::signed(2), claim.clone(), 3));
+		assert_eq!(Proofs::<Test>::get(&claim).0, 3);
+		// The approval is cleared by the transfer.
+		assert_eq!(PoeModule::approved(&claim), None);
+		assert_noop!(
+			PoeModule::transfer_from(Origin::signed(2), claim, 2),
+			Error::<Test>::NotApprovedOrOwner
+		);
+	});
+}
+
+#[test]
+fn approve_requires_owner() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::approve(Origin::signed(2), claim.clone(), 3),
+			Error::<Test>::NotProofOwner
+		);
+		assert_noop!(
+			PoeModule::approve(Origin::signed(1), claim, 1),
+			Error::<Test>::ApproveToCaller
+		);
+	});
+}
+
+#[test]
+fn operator_can_transfer_all_claims() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![1]));
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![2]));
+		assert_ok!(PoeModule::set_approval_for_all(Origin::signed(1), 2, true));
+		assert!(PoeModule::is_approved_for_all(1, 2));
+
+		assert_ok!(PoeModule::transfer_from(Origin::signed(2), vec![1], 3));
+		assert_ok!(PoeModule::transfer_from(Origin::signed(2), vec![2], 3));
+		assert_eq!(Proofs::<Test>::get(vec![2]).0, 3);
+
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![3]));
+		assert_ok!(PoeModule::set_approval_for_all(Origin::signed(1), 2, false));
+		assert_noop!(
+			PoeModule::transfer_from(Origin::signed(2), vec![3], 3),
+			Error::<Test>::NotApprovedOrOwner
+		);
+	});
+}
+
+#[test]
+fn transfer_claim_clears_approval() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::approve(Origin::signed(1), claim.clone(), 2));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), 3, claim.clone()));
+		assert_eq!(PoeModule::approved(&claim), None);
+	});
+}
+
+#[test]
+fn genesis_claims_are_registered() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Proofs::<Test>::get(vec![9, 9]), (3, 0));
+		assert_eq!(PoeModule::claim(&vec![9, 9]), Some((3, 0)));
+		assert_eq!(PoeModule::claim(&vec![1]), None);
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), vec![9, 9]),
+			Error::<Test>::ProofAlreadyClaimed
+		);
+	});
+}
+
+fn run_scheduled_until(n: u64) {
+	System::set_block_number(n);
+	for call in take_scheduled(n) {
+		assert_ok!(call.dispatch_bypass_filter(Origin::root()));
+	}
+}
+
+#[test]
+fn scheduled_transfer_runs_at_its_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Transfer(2), 1),
+			Error::<Test>::ScheduleInPast
+		);
+		assert_noop!(
+			PoeModule::schedule_action(Origin::signed(2), claim.clone(), ScheduledAction::Transfer(2), 5),
+			Error::<Test>::NotProofOwner
+		);
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Transfer(2), 5));
+		assert_noop!(
+			PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Revoke, 5),
+			Error::<Test>::AlreadyScheduled
+		);
+
+		run_scheduled_until(4);
+		assert_eq!(Proofs::<Test>::get(&claim).0, 1);
+
+		run_scheduled_until(5);
+		assert_eq!(Proofs::<Test>::get(&claim), (2, 5));
+	});
+}
+
+#[test]
+fn scheduled_action_keeps_owner_check() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Revoke, 5));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), 2, claim.clone()));
+
+		// The claim changed hands, which cancelled the revocation scheduled by its former owner.
+		assert!(PoeModule::scheduled_actions(&claim).is_empty());
+		assert_eq!(Balances::reserved_balance(1), 0);
+		run_scheduled_until(5);
+		assert!(Proofs::<Test>::contains_key(&claim));
+		assert_eq!(Proofs::<Test>::get(&claim).0, 2);
+	});
+}
+
+#[test]
+fn scheduled_action_can_be_cancelled() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Revoke, 5));
+
+		assert_noop!(
+			PoeModule::cancel_scheduled_action(Origin::signed(2), claim.clone(), 5),
+			Error::<Test>::NotScheduled
+		);
+		assert_ok!(PoeModule::cancel_scheduled_action(Origin::signed(1), claim.clone(), 5));
+
+		run_scheduled_until(5);
+		assert!(Proofs::<Test>::contains_key(&claim));
+	});
+}
+
+#[test]
+fn run_scheduled_requires_root() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::run_scheduled(Origin::signed(3), 3, vec![9, 9], ScheduledAction::Revoke, 0),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn scheduled_action_deposit_is_returned() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Revoke, 5));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Transfer(2), 6));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_eq!(PoeModule::scheduled_actions(&claim), vec![(5, 1), (6, 1)]);
+
+		assert_ok!(PoeModule::cancel_scheduled_action(Origin::signed(1), claim.clone(), 6));
+		assert_eq!(Balances::reserved_balance(1), 5);
+
+		// The deposit is returned even though the action itself ran.
+		run_scheduled_until(5);
+		assert!(!Proofs::<Test>::contains_key(&claim));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(PoeModule::scheduled_actions(&claim).is_empty());
+	});
+}
+
+#[test]
+fn sale_and_revocation_cancel_scheduled_actions() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Revoke, 5));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Transfer(3), 6));
+		assert_ok!(PoeModule::list_claim(Origin::signed(1), claim.clone(), 50));
+		assert_eq!(Balances::reserved_balance(1), 10);
+
+		// The sale cancels the seller's actions and returns their deposits.
+		assert_ok!(PoeModule::buy_claim(Origin::signed(2), claim.clone(), 50));
+		assert!(PoeModule::scheduled_actions(&claim).is_empty());
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(take_scheduled(6).is_empty());
+
+		// The buyer can schedule on the claim again, and revoking it cancels that too.
+		assert_ok!(PoeModule::schedule_action(Origin::signed(2), claim.clone(), ScheduledAction::Revoke, 5));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(2), claim.clone(), ScheduledAction::Revoke, 6));
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(2), claim.clone()));
+		assert!(PoeModule::scheduled_actions(&claim).is_empty());
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert!(take_scheduled(6).is_empty());
+	});
+}
+
+#[test]
+fn scheduled_actions_per_claim_are_capped() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Revoke, 5));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Revoke, 6));
+		assert_noop!(
+			PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Revoke, 7),
+			Error::<Test>::TooManyScheduled
+		);
+
+		assert_ok!(PoeModule::cancel_scheduled_action(Origin::signed(1), claim.clone(), 5));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Revoke, 7));
+	});
+}
+
+#[test]
+fn scheduled_action_respects_call_filter() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::schedule_action(Origin::signed(1), claim.clone(), ScheduledAction::Transfer(2), 5));
+
+		// Transfers are paused, so the scheduler running as root must not move the claim.
+		TRANSFERS_PAUSED.with(|p| *p.borrow_mut() = true);
+		run_scheduled_until(5);
+		assert_eq!(Proofs::<Test>::get(&claim).0, 1);
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn listed_claim_can_be_bought() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::list_claim(Origin::signed(2), claim.clone(), 50),
+			Error::<Test>::NotProofOwner
+		);
+		assert_ok!(PoeModule::list_claim(Origin::signed(1), claim.clone(), 50));
+		assert_eq!(PoeModule::listing(&claim), Some(50));
+
+		assert_noop!(
+			PoeModule::buy_claim(Origin::signed(1), claim.clone(), 50),
+			Error::<Test>::BuyOwnClaim
+		);
+		assert_noop!(
+			PoeModule::buy_claim(Origin::signed(2), claim.clone(), 49),
+			Error::<Test>::PriceTooHigh
+		);
+		assert_ok!(PoeModule::buy_claim(Origin::signed(2), claim.clone(), 50));
+
+		assert_eq!(Proofs::<Test>::get(&claim).0, 2);
+		assert_eq!(PoeModule::listing(&claim), None);
+		assert_eq!(Balances::free_balance(1), 100 - 10 + 50);
+		assert_eq!(Balances::free_balance(2), 100 - 50);
+	});
+}
+
+#[test]
+fn creator_receives_royalty_on_resale() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::set_royalty(Origin::signed(1), claim.clone(), Percent::from_percent(10)));
+		assert_ok!(PoeModule::list_claim(Origin::signed(1), claim.clone(), 50));
+		assert_ok!(PoeModule::buy_claim(Origin::signed(2), claim.clone(), 50));
+
+		// Once sold, the creator can no longer change the royalty.
+		assert_noop!(
+			PoeModule::set_royalty(Origin::signed(1), claim.clone(), Percent::from_percent(50)),
+			Error::<Test>::NotCreator
+		);
+		assert_noop!(
+			PoeModule::set_royalty(Origin::signed(2), claim.clone(), Percent::from_percent(50)),
+			Error::<Test>::NotCreator
+		);
+
+		assert_ok!(PoeModule::list_claim(Origin::signed(2), claim.clone(), 40));
+		assert_ok!(PoeModule::buy_claim(Origin::signed(3), claim.clone(), 40));
+
+		assert_eq!(Proofs::<Test>::get(&claim).0, 3);
+		assert_eq!(Balances::free_balance(1), 100 - 10 + 50 + 4);
+		assert_eq!(Balances::free_balance(2), 100 - 50 + 36);
+		assert_eq!(Balances::free_balance(3), 100 - 40);
+	});
+}
+
+#[test]
+fn royalty_to_reaped_creator_below_existential_deposit_is_skipped() {
+	new_test_ext().execute_with(|| {
+		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::set_royalty(Origin::signed(1), claim.clone(), Percent::from_percent(10)));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), 2, claim.clone()));
+
+		// The creator's account is reaped, and a royalty of 5 can't recreate it.
+		Balances::make_free_balance_be(&1, 0);
+		assert_ok!(PoeModule::list_claim(Origin::signed(2), claim.clone(), 50));
+		assert_ok!(PoeModule::buy_claim(Origin::signed(3), claim.clone(), 50));
+
+		assert_eq!(Proofs::<Test>::get(&claim).0, 3);
+		assert_eq!(Balances::total_balance(&1), 0);
+		assert_eq!(Balances::free_balance(2), 100 + 50);
+		assert_eq!(Balances::free_balance(3), 100 - 50);
+	});
+}
+
+#[test]
+fn failed_purchase_changes_nothing() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::list_claim(Origin::signed(1), claim.clone(), 100));
+
+		// The buyer must stay above the existential deposit.
+		assert_noop!(
+			PoeModule::buy_claim(Origin::signed(2), claim.clone(), 100),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::KeepAlive
+		);
+		assert_eq!(Proofs::<Test>::get(&claim).0, 1);
+		assert_eq!(PoeModule::listing(&claim), Some(100));
+	});
+}
+
+#[test]
+fn unlisting_and_transfers_clear_listing() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::unlist_claim(Origin::signed(1), claim.clone()),
+			Error::<Test>::NotListed
+		);
+		assert_ok!(PoeModule::list_claim(Origin::signed(1), claim.clone(), 50));
+		assert_noop!(
+			PoeModule::unlist_claim(Origin::signed(2), claim.clone()),
+			Error::<Test>::NotProofOwner
+		);
+		assert_ok!(PoeModule::unlist_claim(Origin::signed(1), claim.clone()));
+		assert_eq!(PoeModule::listing(&claim), None);
+
+		assert_ok!(PoeModule::list_claim(Origin::signed(1), claim.clone(), 50));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), 2, claim.clone()));
+		assert_noop!(
+			PoeModule::buy_claim(Origin::signed(3), claim, 50),
+			Error::<Test>::NotListed
+		);
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn upgrade_checks_catch_lost_claims_and_orphaned_data() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::list_claim(Origin::signed(1), claim.clone(), 50));
+
+		assert_ok!(PoeModule::pre_upgrade());
+		assert_ok!(PoeModule::post_upgrade());
+		assert_eq!(PoeModule::post_upgrade(), Err("pre_upgrade did not run"));
+
+		assert_ok!(PoeModule::pre_upgrade());
+		Proofs::<Test>::remove(&claim);
+		assert_eq!(PoeModule::post_upgrade(), Err("claims were added or lost"));
+
+		assert_ok!(PoeModule::pre_upgrade());
+		assert_eq!(PoeModule::post_upgrade(), Err("salt stored for a missing claim"));
+	});
+}
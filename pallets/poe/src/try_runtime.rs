@@ -0,0 +1,39 @@
+//! Checks run around a runtime upgrade by the node's `try-runtime` command.
+
+use frame_support::{ensure, IterableStorageMap, storage::unhashed};
+use crate::{Module, Config, Proofs, ClaimSalts, Approvals, Listings, Creators};
+
+/// Where `pre_upgrade` keeps the number of claims for `post_upgrade`.
+const CLAIM_COUNT_KEY: &[u8] = b":poe:try-runtime:claim-count";
+
+impl<T: Config> Module<T> {
+	/// Record the number of claims before a runtime upgrade.
+	pub fn pre_upgrade() -> Result<(), &'static str> {
+		unhashed::put(CLAIM_COUNT_KEY, &(Proofs::<T>::iter().count() as u64));
+		Ok(())
+	}
+
+	/// Check that the upgrade kept every claim and left nothing behind for a missing claim.
+	pub fn post_upgrade() -> Result<(), &'static str> {
+		let before: u64 = unhashed::take(CLAIM_COUNT_KEY).ok_or("pre_upgrade did not run")?;
+		ensure!(Proofs::<T>::iter().count() as u64 == before, "claims were added or lost");
+
+		ensure!(
+			ClaimSalts::<T>::iter().all(|(proof, _)| Proofs::<T>::contains_key(proof)),
+			"salt stored for a missing claim"
+		);
+		ensure!(
+			Approvals::<T>::iter().all(|(proof, _)| Proofs::<T>::contains_key(proof)),
+			"approval stored for a missing claim"
+		);
+		ensure!(
+			Listings::<T>::iter().all(|(proof, _)| Proofs::<T>::contains_key(proof)),
+			"listing stored for a missing claim"
+		);
+		ensure!(
+			Creators::<T>::iter().all(|(proof, _)| Proofs::<T>::contains_key(proof)),
+			"creator stored for a missing claim"
+		);
+		Ok(())
+	}
+}
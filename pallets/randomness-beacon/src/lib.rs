@@ -0,0 +1,148 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A commit-reveal randomness beacon. Authorities commit to the hash of a secret and reveal
+/// the secret in a later block; every reveal is mixed into the beacon's seed. Unlike the
+/// parent-hash based collective flip, a single block author can't choose the outcome, although
+/// the last authority to reveal can still withhold its secret. The output also mixes in
+/// `VrfRandomness`, such as BABE's VRF output, so it can't be computed in advance from the seed
+/// and block number alone.
+
+use codec::Encode;
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure, StorageMap, StorageValue,
+	traits::{Get, Contains, Randomness},
+};
+use frame_system::ensure_signed;
+use sp_runtime::traits::{Hash, Saturating};
+use sp_std::vec::Vec;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// The accounts allowed to commit to and reveal secrets.
+	type Authorities: Contains<Self::AccountId>;
+
+	/// Number of blocks that must pass between a commitment and its reveal.
+	type RevealDelay: Get<Self::BlockNumber>;
+
+	/// Number of blocks after which an unrevealed commitment expires and can be replaced.
+	type RevealPeriod: Get<Self::BlockNumber>;
+
+	/// Maximum length in bytes of a revealed secret.
+	type MaxSecretLength: Get<u32>;
+
+	/// Randomness from the block production VRF, mixed into every output.
+	type VrfRandomness: Randomness<Self::Hash>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as RandomnessBeacon {
+		/// Pending commitments of authorities and the block in which they were made.
+		Commitments get(fn commitment): map hasher(blake2_128_concat) T::AccountId
+			=> Option<(T::Hash, T::BlockNumber)>;
+		/// The accumulated seed, updated with every revealed secret.
+		Seed get(fn seed): T::Hash;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		Hash = <T as frame_system::Config>::Hash,
+	{
+		/// An authority committed to a secret. [who, commitment]
+		Committed(AccountId, Hash),
+		/// An authority revealed its secret and the seed was updated. [who, new_seed]
+		Revealed(AccountId, Hash),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// Only authorities can take part in the beacon.
+		NotAuthority,
+		/// The authority already has a commitment that hasn't been revealed or expired.
+		AlreadyCommitted,
+		/// The authority has no pending commitment.
+		NoCommitment,
+		/// The commitment can't be revealed before `RevealDelay` blocks have passed.
+		RevealTooEarly,
+		/// The commitment expired before being revealed.
+		CommitmentExpired,
+		/// The revealed secret doesn't match the commitment.
+		InvalidReveal,
+		/// The revealed secret is longer than `MaxSecretLength`.
+		SecretTooLong,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Commit to the hash of a secret that will be revealed in a later block.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn commit(origin, commitment: T::Hash) {
+			let who = ensure_signed(origin)?;
+			ensure!(T::Authorities::contains(&who), Error::<T>::NotAuthority);
+
+			let now = <frame_system::Module<T>>::block_number();
+			if let Some((_, committed_at)) = Commitments::<T>::get(&who) {
+				ensure!(Self::is_expired(committed_at, now), Error::<T>::AlreadyCommitted);
+			}
+
+			Commitments::<T>::insert(&who, (commitment, now));
+
+			Self::deposit_event(RawEvent::Committed(who, commitment));
+		}
+
+		/// Reveal the secret of a pending commitment and mix it into the seed.
+		///
+		/// The secret is at most `MaxSecretLength` bytes, so the weight covers hashing it.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(2, 2)]
+		pub fn reveal(origin, secret: Vec<u8>) {
+			let who = ensure_signed(origin)?;
+			ensure!(secret.len() <= T::MaxSecretLength::get() as usize, Error::<T>::SecretTooLong);
+			ensure!(T::Authorities::contains(&who), Error::<T>::NotAuthority);
+
+			let (commitment, committed_at) = Commitments::<T>::get(&who)
+				.ok_or(Error::<T>::NoCommitment)?;
+			let now = <frame_system::Module<T>>::block_number();
+			ensure!(now >= committed_at.saturating_add(T::RevealDelay::get()), Error::<T>::RevealTooEarly);
+			ensure!(!Self::is_expired(committed_at, now), Error::<T>::CommitmentExpired);
+			ensure!(T::Hashing::hash(&secret) == commitment, Error::<T>::InvalidReveal);
+
+			Commitments::<T>::remove(&who);
+			let seed = T::Hashing::hash_of(&(Seed::<T>::get(), secret));
+			Seed::<T>::put(seed);
+
+			Self::deposit_event(RawEvent::Revealed(who, seed));
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	fn is_expired(committed_at: T::BlockNumber, now: T::BlockNumber) -> bool {
+		now > committed_at.saturating_add(T::RevealPeriod::get())
+	}
+}
+
+impl<T: Config> Randomness<T::Hash> for Module<T> {
+	/// Derive a value from the beacon seed and the VRF randomness that is unique to `subject`
+	/// and the current block.
+	fn random(subject: &[u8]) -> T::Hash {
+		let block_number = <frame_system::Module<T>>::block_number();
+		let vrf = T::VrfRandomness::random(subject);
+		(subject, block_number, Seed::<T>::get(), vrf).using_encoded(T::Hashing::hash)
+	}
+}
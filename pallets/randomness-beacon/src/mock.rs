@@ -0,0 +1,96 @@
+use crate as pallet_randomness_beacon;
+use crate::Config;
+use sp_core::H256;
+use frame_support::{parameter_types, traits::{Contains, Randomness}};
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash, IdentityLookup}, testing::Header,
+};
+use std::cell::RefCell;
+use frame_system as system;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		RandomnessBeacon: pallet_randomness_beacon::{Module, Call, Storage},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const RevealDelay: u64 = 2;
+	pub const RevealPeriod: u64 = 10;
+	pub const MaxSecretLength: u32 = 32;
+}
+
+/// Accounts 1 and 2 are the authorities of the mock chain.
+pub struct TestAuthorities;
+impl Contains<u64> for TestAuthorities {
+	fn sorted_members() -> Vec<u64> {
+		vec![1, 2]
+	}
+}
+
+thread_local! {
+	static VRF_OUTPUT: RefCell<H256> = RefCell::new(H256::zero());
+}
+
+/// Stands in for BABE, returning randomness derived from the output set by `set_vrf_output`.
+pub struct TestVrf;
+impl Randomness<H256> for TestVrf {
+	fn random(subject: &[u8]) -> H256 {
+		VRF_OUTPUT.with(|v| BlakeTwo256::hash_of(&(subject, *v.borrow())))
+	}
+}
+
+/// Set the VRF output of the current block.
+pub fn set_vrf_output(output: H256) {
+	VRF_OUTPUT.with(|v| *v.borrow_mut() = output);
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+impl Config for Test {
+	type Event = ();
+	type Authorities = TestAuthorities;
+	type RevealDelay = RevealDelay;
+	type RevealPeriod = RevealPeriod;
+	type MaxSecretLength = MaxSecretLength;
+	type VrfRandomness = TestVrf;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	set_vrf_output(H256::zero());
+	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+}
@@ -0,0 +1,88 @@
+use crate::{Error, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::Randomness};
+use codec::Encode;
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+#[test]
+fn commit_requires_authority() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			RandomnessBeacon::commit(Origin::signed(3), BlakeTwo256::hash(b"secret")),
+			Error::<Test>::NotAuthority
+		);
+	});
+}
+
+#[test]
+fn reveal_updates_seed() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(RandomnessBeacon::commit(Origin::signed(1), BlakeTwo256::hash(b"secret")));
+		assert_noop!(
+			RandomnessBeacon::commit(Origin::signed(1), BlakeTwo256::hash(b"other")),
+			Error::<Test>::AlreadyCommitted
+		);
+		assert_noop!(
+			RandomnessBeacon::reveal(Origin::signed(1), b"secret".to_vec()),
+			Error::<Test>::RevealTooEarly
+		);
+
+		System::set_block_number(3);
+		let before = RandomnessBeacon::random(b"subject");
+		assert_noop!(
+			RandomnessBeacon::reveal(Origin::signed(1), b"wrong".to_vec()),
+			Error::<Test>::InvalidReveal
+		);
+		assert_ok!(RandomnessBeacon::reveal(Origin::signed(1), b"secret".to_vec()));
+		assert_eq!(RandomnessBeacon::commitment(1), None);
+		assert_ne!(RandomnessBeacon::random(b"subject"), before);
+	});
+}
+
+#[test]
+fn expired_commitment_can_be_replaced() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(RandomnessBeacon::commit(Origin::signed(2), BlakeTwo256::hash(b"secret")));
+
+		System::set_block_number(12);
+		assert_noop!(
+			RandomnessBeacon::reveal(Origin::signed(2), b"secret".to_vec()),
+			Error::<Test>::CommitmentExpired
+		);
+		assert_ok!(RandomnessBeacon::commit(Origin::signed(2), BlakeTwo256::hash(b"fresh")));
+	});
+}
+
+#[test]
+fn reveal_rejects_long_secret() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let secret = vec![7u8; 33];
+		assert_ok!(RandomnessBeacon::commit(Origin::signed(1), BlakeTwo256::hash(&secret)));
+
+		System::set_block_number(3);
+		assert_noop!(
+			RandomnessBeacon::reveal(Origin::signed(1), secret),
+			Error::<Test>::SecretTooLong
+		);
+		assert!(RandomnessBeacon::commitment(1).is_some());
+	});
+}
+
+#[test]
+fn output_depends_on_vrf_randomness() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		let seed_only = (b"subject".as_ref(), 5u64, RandomnessBeacon::seed()).using_encoded(BlakeTwo256::hash);
+		let before = RandomnessBeacon::random(b"subject");
+		assert_ne!(before, seed_only);
+
+		// Same block, same seed, different VRF output.
+		set_vrf_output(BlakeTwo256::hash(b"vrf"));
+		assert_ne!(RandomnessBeacon::random(b"subject"), before);
+		assert_ne!(RandomnessBeacon::random(b"subject"), seed_only);
+		assert_eq!(RandomnessBeacon::seed(), H256::zero());
+	});
+}
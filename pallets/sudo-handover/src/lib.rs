@@ -0,0 +1,60 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Records the one-way handover of root from the sudo key to on-chain governance. The runtime's
+/// `BaseCallFilter` rejects every `Sudo` call once the handover happened, so root can then only be
+/// reached through governance.
+
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure, StorageValue};
+use frame_system::ensure_root;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event> + Into<<Self as frame_system::Config>::Event>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as SudoHandover {
+		/// Whether the sudo key has handed root over to governance.
+		HandedOver get(fn handed_over): bool;
+	}
+}
+
+decl_event!(
+	pub enum Event {
+		/// The sudo key handed root over to governance and can no longer be used.
+		HandedOver,
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// Root has already been handed over.
+		AlreadyHandedOver,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Permanently disable the sudo key. Must be called by root, i.e. through `sudo.sudo`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn hand_over(origin) {
+			ensure_root(origin)?;
+			ensure!(!HandedOver::get(), Error::<T>::AlreadyHandedOver);
+
+			HandedOver::put(true);
+
+			Self::deposit_event(Event::HandedOver);
+		}
+	}
+}
@@ -0,0 +1,21 @@
+use crate::{Error, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use sp_runtime::DispatchError;
+
+#[test]
+fn hand_over_requires_root() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(SudoHandover::hand_over(Origin::signed(1)), DispatchError::BadOrigin);
+		assert!(!SudoHandover::handed_over());
+	});
+}
+
+#[test]
+fn hand_over_works_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SudoHandover::hand_over(Origin::root()));
+		assert!(SudoHandover::handed_over());
+
+		assert_noop!(SudoHandover::hand_over(Origin::root()), Error::<Test>::AlreadyHandedOver);
+	});
+}
@@ -4,7 +4,8 @@
 /// Learn more about FRAME and the core library of Substrate FRAME pallets:
 /// https://substrate.dev/docs/en/knowledgebase/runtime/frame
 
-use frame_support::{decl_module, decl_storage, decl_event, decl_error, dispatch, traits::Get};
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, dispatch, traits::{Get, Randomness}};
+use codec::{Encode, Decode};
 use frame_system::ensure_signed;
 
 #[cfg(test)]
@@ -14,9 +15,12 @@ mod mock;
 mod tests;
 
 /// Configure the pallet by specifying the parameters and types on which it depends.
-pub trait Trait: frame_system::Trait {
+pub trait Config: frame_system::Config {
 	/// Because this pallet emits events, it depends on the runtime's definition of an event.
-	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// Source of randomness for lottery-style features.
+	type Randomness: Randomness<Self::Hash>;
 }
 
 // The pallet's runtime storage items.
@@ -25,7 +29,7 @@ decl_storage! {
 	// A unique name is used to ensure that the pallet's storage items are isolated.
 	// This name may be updated, but each pallet in the runtime must use a unique name.
 	// ---------------------------------vvvvvvvvvvvvvv
-	trait Store for Module<T: Trait> as TemplateModule {
+	trait Store for Module<T: Config> as TemplateModule {
 		// Learn more about declaring storage items:
 		// https://substrate.dev/docs/en/knowledgebase/runtime/storage#declaring-storage-items
 		Something get(fn something): Option<u32>;
@@ -35,7 +39,7 @@ decl_storage! {
 // Pallets use events to inform users when important changes are made.
 // https://substrate.dev/docs/en/knowledgebase/runtime/events
 decl_event!(
-	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
+	pub enum Event<T> where AccountId = <T as frame_system::Config>::AccountId {
 		/// Event documentation should end with an array that provides descriptive names for event
 		/// parameters. [something, who]
 		SomethingStored(u32, AccountId),
@@ -44,7 +48,7 @@ decl_event!(
 
 // Errors inform users that something went wrong.
 decl_error! {
-	pub enum Error for Module<T: Trait> {
+	pub enum Error for Module<T: Config> {
 		/// Error names should be descriptive.
 		NoneValue,
 		/// Errors should have helpful documentation associated with them.
@@ -56,7 +60,7 @@ decl_error! {
 // These functions materialize as "extrinsics", which are often compared to transactions.
 // Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 decl_module! {
-	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
 		// Errors must be initialized if they are used by the pallet.
 		type Error = Error<T>;
 
@@ -99,5 +103,20 @@ decl_module! {
 				},
 			}
 		}
+
+		/// An example dispatchable that stores a random value drawn from the runtime's
+		/// randomness source, like a lottery draw.
+		#[weight = 10_000 + T::DbWeight::get().writes(1)]
+		pub fn draw_something(origin) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let seed = T::Randomness::random(&(b"template/draw", &who).encode());
+			// A hash is at least 4 bytes long, so decoding a u32 from it never fails.
+			let something = u32::decode(&mut seed.as_ref()).unwrap_or_default();
+			Something::put(something);
+
+			Self::deposit_event(RawEvent::SomethingStored(something, who));
+			Ok(())
+		}
 	}
 }
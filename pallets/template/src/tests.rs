@@ -21,3 +21,11 @@ fn correct_error_for_none_value() {
 		);
 	});
 }
+
+#[test]
+fn draw_something_stores_a_value() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::draw_something(Origin::signed(1)));
+		assert!(TemplateModule::something().is_some());
+	});
+}
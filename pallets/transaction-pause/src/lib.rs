@@ -0,0 +1,118 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A registry of paused pallets and calls, consulted by the runtime's `BaseCallFilter` so that
+/// dispatch can be frozen during an incident without a runtime upgrade.
+
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure, StorageMap, StorageDoubleMap,
+	traits::EnsureOrigin,
+};
+use sp_std::vec::Vec;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event> + Into<<Self as frame_system::Config>::Event>;
+
+	/// The origin allowed to pause and resume calls.
+	type PauseOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as TransactionPause {
+		/// Pallets whose calls are all paused, keyed by their name in `construct_runtime!`.
+		PausedPallets get(fn paused_pallet): map hasher(blake2_128_concat) Vec<u8> => bool;
+		/// Individual calls that are paused. [pallet_name, call_name]
+		PausedCalls get(fn paused_call):
+			double_map hasher(blake2_128_concat) Vec<u8>, hasher(blake2_128_concat) Vec<u8> => bool;
+	}
+}
+
+decl_event!(
+	pub enum Event {
+		/// All calls of a pallet were paused. [pallet_name]
+		PalletPaused(Vec<u8>),
+		/// A paused pallet was resumed. [pallet_name]
+		PalletUnpaused(Vec<u8>),
+		/// A single call was paused. [pallet_name, call_name]
+		CallPaused(Vec<u8>, Vec<u8>),
+		/// A paused call was resumed. [pallet_name, call_name]
+		CallUnpaused(Vec<u8>, Vec<u8>),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The pallet or call is already paused.
+		AlreadyPaused,
+		/// The pallet or call is not paused.
+		NotPaused,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Pause every call of `pallet_name`. Must be called by `PauseOrigin`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn pause_pallet(origin, pallet_name: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(!PausedPallets::contains_key(&pallet_name), Error::<T>::AlreadyPaused);
+
+			PausedPallets::insert(&pallet_name, true);
+
+			Self::deposit_event(Event::PalletPaused(pallet_name));
+		}
+
+		/// Resume the calls of a paused pallet. Must be called by `PauseOrigin`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn unpause_pallet(origin, pallet_name: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(PausedPallets::contains_key(&pallet_name), Error::<T>::NotPaused);
+
+			PausedPallets::remove(&pallet_name);
+
+			Self::deposit_event(Event::PalletUnpaused(pallet_name));
+		}
+
+		/// Pause a single `(pallet, call)` pair. Must be called by `PauseOrigin`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn pause_call(origin, pallet_name: Vec<u8>, call_name: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(!PausedCalls::contains_key(&pallet_name, &call_name), Error::<T>::AlreadyPaused);
+
+			PausedCalls::insert(&pallet_name, &call_name, true);
+
+			Self::deposit_event(Event::CallPaused(pallet_name, call_name));
+		}
+
+		/// Resume a paused `(pallet, call)` pair. Must be called by `PauseOrigin`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn unpause_call(origin, pallet_name: Vec<u8>, call_name: Vec<u8>) {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(PausedCalls::contains_key(&pallet_name, &call_name), Error::<T>::NotPaused);
+
+			PausedCalls::remove(&pallet_name, &call_name);
+
+			Self::deposit_event(Event::CallUnpaused(pallet_name, call_name));
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// Whether the call `call_name` of `pallet_name` is currently paused, either on its own or
+	/// because its whole pallet is.
+	pub fn is_paused(pallet_name: &str, call_name: &str) -> bool {
+		PausedPallets::get(pallet_name.as_bytes())
+			|| PausedCalls::get(pallet_name.as_bytes(), call_name.as_bytes())
+	}
+}
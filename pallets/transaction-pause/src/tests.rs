@@ -0,0 +1,48 @@
+use crate::{Error, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use sp_runtime::DispatchError;
+
+#[test]
+fn pause_pallet_requires_root() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TransactionPause::pause_pallet(Origin::signed(1), b"PoeModule".to_vec()),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn pause_and_unpause_pallet_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TransactionPause::pause_pallet(Origin::root(), b"PoeModule".to_vec()));
+		assert!(TransactionPause::is_paused("PoeModule", "create_claim"));
+		assert!(!TransactionPause::is_paused("Balances", "transfer"));
+		assert_noop!(
+			TransactionPause::pause_pallet(Origin::root(), b"PoeModule".to_vec()),
+			Error::<Test>::AlreadyPaused
+		);
+
+		assert_ok!(TransactionPause::unpause_pallet(Origin::root(), b"PoeModule".to_vec()));
+		assert!(!TransactionPause::is_paused("PoeModule", "create_claim"));
+	});
+}
+
+#[test]
+fn pause_single_call_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TransactionPause::pause_call(
+			Origin::root(), b"PoeModule".to_vec(), b"create_claim".to_vec(),
+		));
+		assert!(TransactionPause::is_paused("PoeModule", "create_claim"));
+		assert!(!TransactionPause::is_paused("PoeModule", "revoke_claim"));
+
+		assert_ok!(TransactionPause::unpause_call(
+			Origin::root(), b"PoeModule".to_vec(), b"create_claim".to_vec(),
+		));
+		assert_noop!(
+			TransactionPause::unpause_call(Origin::root(), b"PoeModule".to_vec(), b"create_claim".to_vec()),
+			Error::<Test>::NotPaused
+		);
+	});
+}
@@ -0,0 +1,151 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A root-managed set of PoA validators. The set is handed to `pallet_session` as its
+/// `SessionManager`, so additions and removals take effect at the next session boundary.
+
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure, StorageValue,
+	traits::{EnsureOrigin, Contains},
+};
+use sp_std::{marker::PhantomData, vec::Vec};
+use sp_runtime::traits::Convert;
+use pallet_session::{SessionManager, SessionIndex, historical};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+	/// Because this pallet emits events, it depends on the runtime's definition of an event.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// The origin allowed to add or remove validators.
+	type AddRemoveOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as ValidatorSet {
+		/// The validators that will be handed to the session at the next rotation.
+		Validators get(fn validators) config(): Vec<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where AccountId = <T as frame_system::Config>::AccountId {
+		/// A validator was added and joins at the next session. [who]
+		ValidatorAdded(AccountId),
+		/// A validator was removed and leaves at the next session. [who]
+		ValidatorRemoved(AccountId),
+		/// A validator was removed after being reported for an offence. [who]
+		OffenderRemoved(AccountId),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The account is already a validator.
+		AlreadyValidator,
+		/// The account is not a validator.
+		NotValidator,
+		/// The last validator can't be removed, otherwise the chain would stall.
+		TooFewValidators,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Add `validator` to the set. It starts authoring once the session rotates and its
+		/// session keys have been registered via `session.set_keys`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn add_validator(origin, validator: T::AccountId) {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			let mut validators = Validators::<T>::get();
+			ensure!(!validators.contains(&validator), Error::<T>::AlreadyValidator);
+			validators.push(validator.clone());
+			Validators::<T>::put(validators);
+
+			Self::deposit_event(RawEvent::ValidatorAdded(validator));
+		}
+
+		/// Remove `validator` from the set at the next session rotation.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+		pub fn remove_validator(origin, validator: T::AccountId) {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			let mut validators = Validators::<T>::get();
+			let position = validators.iter().position(|v| v == &validator)
+				.ok_or(Error::<T>::NotValidator)?;
+			ensure!(validators.len() > 1, Error::<T>::TooFewValidators);
+			validators.remove(position);
+			Validators::<T>::put(validators);
+
+			Self::deposit_event(RawEvent::ValidatorRemoved(validator));
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// Remove a reported offender from the set at the next session rotation. The last
+	/// validator is kept so that the chain doesn't stall.
+	pub fn remove_offender(who: &T::AccountId) {
+		let mut validators = Validators::<T>::get();
+		if validators.len() <= 1 {
+			return;
+		}
+		if let Some(position) = validators.iter().position(|v| v == who) {
+			validators.remove(position);
+			Validators::<T>::put(validators);
+
+			Self::deposit_event(RawEvent::OffenderRemoved(who.clone()));
+		}
+	}
+}
+
+impl<T: Config> Contains<T::AccountId> for Module<T> {
+	fn sorted_members() -> Vec<T::AccountId> {
+		let mut validators = Self::validators();
+		validators.sort();
+		validators
+	}
+}
+
+impl<T: Config> SessionManager<T::AccountId> for Module<T> {
+	fn new_session(_new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+		Some(Self::validators())
+	}
+
+	fn end_session(_end_index: SessionIndex) {}
+
+	fn start_session(_start_index: SessionIndex) {}
+}
+
+impl<T: Config> historical::SessionManager<T::AccountId, ()> for Module<T> {
+	fn new_session(new_index: SessionIndex) -> Option<Vec<(T::AccountId, ())>> {
+		<Self as SessionManager<_>>::new_session(new_index)
+			.map(|validators| validators.into_iter().map(|v| (v, ())).collect())
+	}
+
+	fn end_session(end_index: SessionIndex) {
+		<Self as SessionManager<_>>::end_session(end_index)
+	}
+
+	fn start_session(start_index: SessionIndex) {
+		<Self as SessionManager<_>>::start_session(start_index)
+	}
+}
+
+/// Validators carry no exposure in a PoA chain, so their full identification is empty.
+pub struct FullIdentificationOf<T>(PhantomData<T>);
+impl<T: Config> Convert<T::AccountId, Option<()>> for FullIdentificationOf<T> {
+	fn convert(_validator: T::AccountId) -> Option<()> {
+		Some(())
+	}
+}
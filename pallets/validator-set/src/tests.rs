@@ -0,0 +1,44 @@
+use crate::{Error, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use pallet_session::SessionManager;
+use sp_runtime::DispatchError;
+
+#[test]
+fn genesis_validators_start_the_session() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ValidatorSet::new_session(0), Some(vec![1, 2]));
+	});
+}
+
+#[test]
+fn add_validator_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ValidatorSet::add_validator(Origin::signed(1), 3), DispatchError::BadOrigin);
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 3));
+		assert_eq!(ValidatorSet::new_session(1), Some(vec![1, 2, 3]));
+		assert_noop!(ValidatorSet::add_validator(Origin::root(), 3), Error::<Test>::AlreadyValidator);
+	});
+}
+
+#[test]
+fn remove_validator_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 1));
+		assert_eq!(ValidatorSet::validators(), vec![2]);
+		assert_noop!(ValidatorSet::remove_validator(Origin::root(), 1), Error::<Test>::NotValidator);
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::root(), 2),
+			Error::<Test>::TooFewValidators
+		);
+	});
+}
+
+#[test]
+fn remove_offender_keeps_last_validator() {
+	new_test_ext().execute_with(|| {
+		ValidatorSet::remove_offender(&1);
+		assert_eq!(ValidatorSet::validators(), vec![2]);
+		ValidatorSet::remove_offender(&2);
+		assert_eq!(ValidatorSet::validators(), vec![2]);
+	});
+}
@@ -0,0 +1,42 @@
+//! Runtime API definition for the wallet RPC.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Encode, Decode};
+use sp_runtime::RuntimeDebug;
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+/// The balances of an account, split into what it can spend and what vesting still holds back.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WalletBalances<Balance> {
+	/// The free balance, including what is locked.
+	pub free: Balance,
+	/// The reserved balance.
+	pub reserved: Balance,
+	/// The part of the free balance that can't be transferred because of locks.
+	pub frozen: Balance,
+	/// The amount originally locked by the vesting schedule.
+	pub vesting_total: Balance,
+	/// The amount the vesting schedule still locks at this block.
+	pub vesting_locked: Balance,
+	/// The amount the vesting schedule has released so far. Until `vesting.vest` is called it
+	/// may still be counted in `frozen`.
+	pub vested: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Queries over account balances for wallets.
+	pub trait WalletApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The balances of `who`, with the state of its vesting schedule.
+		fn balances(who: AccountId) -> WalletBalances<Balance>;
+
+		/// The SS58 prefix addresses of this chain are encoded with.
+		fn ss58_prefix() -> u8;
+	}
+}
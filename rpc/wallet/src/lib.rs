@@ -0,0 +1,75 @@
+//! RPC interface exposing locked and vested balances to wallets.
+
+use std::sync::Arc;
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+pub use wallet_rpc_runtime_api::{WalletApi as WalletRuntimeApi, WalletBalances};
+
+/// Wallet RPC methods.
+#[rpc]
+pub trait WalletApi<BlockHash, AccountId, Balance> {
+	/// The balances of `who` at the given block, or the best block, including how much of its
+	/// vesting schedule is still locked and how much has been released.
+	#[rpc(name = "wallet_balances")]
+	fn balances(&self, who: AccountId, at: Option<BlockHash>) -> Result<WalletBalances<Balance>>;
+
+	/// The SS58 prefix the runtime at the given block, or the best block, encodes addresses with.
+	#[rpc(name = "wallet_ss58Prefix")]
+	fn ss58_prefix(&self, at: Option<BlockHash>) -> Result<u8>;
+}
+
+/// Error code returned when the runtime call fails.
+const RUNTIME_ERROR: i64 = 1;
+
+/// An implementation of the wallet RPC methods.
+pub struct Wallet<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Wallet<C, Block> {
+	/// Create a new instance backed by `client`.
+	pub fn new(client: Arc<C>) -> Self {
+		Wallet { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId, Balance> WalletApi<<Block as BlockT>::Hash, AccountId, Balance>
+	for Wallet<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: WalletRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec,
+	Balance: Codec,
+{
+	fn balances(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<WalletBalances<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.balances(&at, who).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to query wallet balances.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+
+	fn ss58_prefix(&self, at: Option<<Block as BlockT>::Hash>) -> Result<u8> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.ss58_prefix(&at).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to query the SS58 prefix.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
@@ -0,0 +1,733 @@
+//! Some configurable implementations as associated type for the runtime.
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use sp_runtime::{Perbill, RuntimeDebug, DispatchError, traits::Dispatchable};
+use sp_staking::{SessionIndex, offence::{OnOffenceHandler, OffenceDetails}};
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::{OnUnbalanced, Currency, Imbalance, Get, InstanceFilter},
+	weights::{
+		Weight, GetDispatchInfo, WeightToFeePolynomial, WeightToFeeCoefficients, WeightToFeeCoefficient,
+		constants::ExtrinsicBaseWeight,
+	},
+};
+use smallvec::smallvec;
+use crate::{
+	Balances, Treasury, Authorship, Session, ValidatorSet, Runtime, AccountId, AuthorFeeShare, Call,
+	Balance, CREATE_CLAIM_FEE, PoeModule, Identity, BlockNumber, IdentityJudgement, AssetTxPayment,
+	MaxClaimLength, Origin,
+};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
+};
+use pallet_poe::ChargeClaimFee;
+use pallet_poe_runtime_api::ClaimDetails;
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Credits the block author with the imbalance it is given.
+pub struct Author;
+impl OnUnbalanced<NegativeImbalance> for Author {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		Balances::resolve_creating(&Authorship::author(), amount);
+	}
+}
+
+/// Splits transaction fees and tips between the block author, who receives `AuthorFeeShare`,
+/// and the treasury, which receives the rest.
+pub struct DealWithFees;
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item=NegativeImbalance>) {
+		if let Some(fees) = fees_then_tips.next() {
+			let author_share = AuthorFeeShare::get().deconstruct() as u32;
+			let treasury_share = 100 - author_share;
+			let mut split = fees.ration(treasury_share, author_share);
+			if let Some(tips) = fees_then_tips.next() {
+				tips.ration_merge_into(treasury_share, author_share, &mut split);
+			}
+			Treasury::on_unbalanced(split.0);
+			Author::on_unbalanced(split.1);
+		}
+	}
+}
+
+/// Maps weight to fee linearly, such that the weight of a basic `create_claim`, including the
+/// extrinsic base weight, costs `CREATE_CLAIM_FEE` before the fee multiplier is applied.
+pub struct WeightToFee;
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = Balance;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		let claim_weight = pallet_poe::Call::<Runtime>::create_claim(Vec::new()).get_dispatch_info().weight;
+		let p = CREATE_CLAIM_FEE;
+		let q = Balance::from(ExtrinsicBaseWeight::get() + claim_weight);
+		smallvec![WeightToFeeCoefficient {
+			degree: 1,
+			negative: false,
+			coeff_frac: Perbill::from_rational_approximation(p % q, q),
+			coeff_integer: p / q,
+		}]
+	}
+}
+
+/// Charges the claim fee in the asset the transaction fee is paid in, at the same rate, and in
+/// the native currency otherwise.
+pub struct ClaimFeeInFeeAsset;
+impl ChargeClaimFee<AccountId, Balance> for ClaimFeeInFeeAsset {
+	fn charge(who: &AccountId, fee: Balance) -> DispatchResult {
+		match AssetTxPayment::fee_asset(who) {
+			Some(asset_id) => AssetTxPayment::charge_in_asset(who, asset_id, fee),
+			None => pallet_poe::NativeClaimFee::<Runtime>::charge(who, fee),
+		}
+	}
+}
+
+/// The kinds of calls a proxy account may make on behalf of its delegator.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug)]
+pub enum ProxyType {
+	/// Every call.
+	Any,
+	/// Creating, revoking, transferring and approving claims only, optionally batched. Buying,
+	/// selling, royalties and scheduling, which move balances or outlive the call, are forbidden.
+	PoeOnly,
+	/// Only calls known not to move balances, assets or claims to someone else. New pallets
+	/// and calls stay forbidden until they are added here.
+	NonTransfer,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		ProxyType::Any
+	}
+}
+
+impl InstanceFilter<Call> for ProxyType {
+	fn filter(&self, c: &Call) -> bool {
+		// Calls wrapped by `Utility` are allowed only if each of them is.
+		match c {
+			Call::Utility(pallet_utility::Call::batch(calls)) |
+			Call::Utility(pallet_utility::Call::batch_all(calls)) =>
+				return calls.iter().all(|call| self.filter(call)),
+			Call::Utility(pallet_utility::Call::as_derivative(_, call)) => return self.filter(call),
+			_ => {},
+		}
+
+		match self {
+			ProxyType::Any => true,
+			ProxyType::PoeOnly => matches!(c,
+				Call::PoeModule(pallet_poe::Call::create_claim(..)) |
+				Call::PoeModule(pallet_poe::Call::revoke_claim(..)) |
+				Call::PoeModule(pallet_poe::Call::transfer_claim(..)) |
+				Call::PoeModule(pallet_poe::Call::approve(..)) |
+				Call::PoeModule(pallet_poe::Call::set_approval_for_all(..)) |
+				Call::PoeModule(pallet_poe::Call::transfer_from(..))
+			),
+			ProxyType::NonTransfer => matches!(c,
+				Call::System(..) |
+				Call::Session(..) |
+				Call::Identity(..) |
+				Call::Council(..) |
+				Call::Democracy(..) |
+				Call::RandomnessBeacon(..) |
+				Call::TemplateModule(..) |
+				Call::Vesting(pallet_vesting::Call::vest(..)) |
+				Call::Vesting(pallet_vesting::Call::vest_other(..)) |
+				Call::PoeModule(pallet_poe::Call::create_claim(..)) |
+				Call::PoeModule(pallet_poe::Call::revoke_claim(..)) |
+				Call::PoeModule(pallet_poe::Call::set_royalty(..)) |
+				Call::PoeModule(pallet_poe::Call::unlist_claim(..)) |
+				Call::PoeModule(pallet_poe::Call::cancel_scheduled_action(..)) |
+				Call::PoeModule(pallet_poe::Call::schedule_action(_, pallet_poe::ScheduledAction::Revoke, _))
+			),
+		}
+	}
+
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			// `PoeOnly` allows claim transfers, which `NonTransfer` forbids.
+			_ => false,
+		}
+	}
+}
+
+/// Lets contracts use proof-of-existence claims. The input of every function is the proof
+/// itself, without a length prefix.
+///
+/// - `1`: Writes the SCALE-encoded `Option<(AccountId, BlockNumber)>` claim record of the proof.
+/// - `2`: Creates a claim owned by the calling contract, which pays the claim fee. The call goes
+///   through `BaseFilter`, so it fails while `create_claim` is paused.
+pub struct PoeExtension;
+impl ChainExtension for PoeExtension {
+	fn call<E: Ext>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal, DispatchError> where
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		let mut env = env.buf_in_buf_out();
+		let len = env.in_len();
+		if len > MaxClaimLength::get() {
+			return Err(pallet_poe::Error::<Runtime>::ProofTooLong.into());
+		}
+		let proof = env.read(len)?;
+
+		match func_id {
+			1 => {
+				env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(1))?;
+				env.write(&PoeModule::claim(&proof).encode(), false, None)?;
+			},
+			2 => {
+				let call = Call::PoeModule(pallet_poe::Call::create_claim(proof));
+				env.charge_weight(call.get_dispatch_info().weight)?;
+				let contract = AccountId::decode(&mut env.ext().address().as_ref())
+					.map_err(|_| DispatchError::Other("Invalid contract address"))?;
+				call.dispatch(Origin::signed(contract)).map_err(|e| e.error)?;
+			},
+			_ => return Err(DispatchError::Other("Unknown PoE chain extension function")),
+		}
+
+		Ok(RetVal::Converging(0))
+	}
+}
+
+type IdentificationTuple = pallet_session::historical::IdentificationTuple<Runtime>;
+
+/// Disables reported authorities for the rest of the session and drops them from the
+/// validator set at the next rotation. There is no stake to slash in this PoA chain.
+pub struct DisableOffenders;
+impl OnOffenceHandler<AccountId, IdentificationTuple, Weight> for DisableOffenders {
+	fn on_offence(
+		offenders: &[OffenceDetails<AccountId, IdentificationTuple>],
+		_slash_fraction: &[Perbill],
+		_session: SessionIndex,
+	) -> Result<Weight, ()> {
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+		let mut weight = 0;
+		for details in offenders {
+			let (who, _) = &details.offender;
+			let _ = Session::disable(who);
+			ValidatorSet::remove_offender(who);
+			weight += db_weight.reads_writes(2, 2);
+		}
+		Ok(weight)
+	}
+
+	fn can_report() -> bool {
+		true
+	}
+}
+
+/// The claim record of `proof` with its owner's identity. The display name is only given out
+/// once a registrar judged the identity as reasonable or known good.
+pub fn claim_details(
+	proof: &[u8],
+) -> Option<ClaimDetails<AccountId, BlockNumber, IdentityJudgement>> {
+	PoeModule::claim(proof).map(|(owner, block_number)| {
+		let registration = Identity::identity(&owner);
+		let judgements = registration.as_ref()
+			.map(|r| r.judgements.to_vec())
+			.unwrap_or_default();
+		let verified = judgements.iter().any(|(_, judgement)| matches!(
+			judgement,
+			IdentityJudgement::Reasonable | IdentityJudgement::KnownGood
+		));
+		let display = match registration.map(|r| r.info.display) {
+			Some(pallet_identity::Data::Raw(name)) if verified => Some(name),
+			_ => None,
+		};
+		ClaimDetails { owner, block_number, display, judgements }
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use sp_core::{sr25519, ed25519};
+	use sp_consensus_babe::{
+		BABE_ENGINE_ID, AuthorityId as BabeId,
+		digests::{PreDigest, SecondaryPlainPreDigest},
+	};
+	use sp_finality_grandpa::AuthorityId as GrandpaId;
+	use sp_runtime::generic::Digest;
+	use frame_system::InitKind;
+	use frame_support::weights::{DispatchInfo, DispatchClass};
+	use sp_runtime::{FixedPointNumber, traits::{Convert, Hash}};
+	use pallet_transaction_payment::Multiplier;
+	use frame_support::{assert_ok, traits::{Filter, EnsureOrigin}};
+	use crate::{
+		Runtime, System, DigestItem, TransactionPayment, BlockWeights, MinimumMultiplier,
+		TargetBlockFullness, DAYS, UNITS, opaque::SessionKeys, BaseFilter, SudoHandover, Origin,
+		CouncilCollective, Contracts, TransactionPause, Proxy,
+	};
+
+	type FeeMultiplierUpdate = <Runtime as pallet_transaction_payment::Config>::FeeMultiplierUpdate;
+
+	fn author_account(seed: u8) -> AccountId {
+		AccountId::from([seed; 32])
+	}
+
+	fn session_keys(seed: u8) -> SessionKeys {
+		SessionKeys {
+			babe: BabeId::from(sr25519::Public::from_raw([seed + 100; 32])),
+			grandpa: GrandpaId::from(ed25519::Public::from_raw([seed + 100; 32])),
+		}
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let validators = vec![author_account(1), author_account(2)];
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_validator_set::GenesisConfig::<Runtime> { validators: validators.clone() }
+			.assimilate_storage(&mut t).unwrap();
+		pallet_session::GenesisConfig::<Runtime> {
+			keys: validators.iter().enumerate()
+				.map(|(i, v)| (v.clone(), v.clone(), session_keys(i as u8 + 1)))
+				.collect(),
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	fn initialize_block_by_authority(authority_index: u32) {
+		let pre_digest = PreDigest::SecondaryPlain(SecondaryPlainPreDigest {
+			authority_index,
+			slot: 1.into(),
+		});
+		let digest = Digest {
+			logs: vec![DigestItem::PreRuntime(BABE_ENGINE_ID, pre_digest.encode())],
+		};
+		System::initialize(&1, &Default::default(), &digest, InitKind::Full);
+	}
+
+	#[test]
+	fn finds_babe_author_from_pre_digest() {
+		new_test_ext().execute_with(|| {
+			initialize_block_by_authority(1);
+			assert_eq!(Authorship::author(), author_account(2));
+		});
+	}
+
+	#[test]
+	fn offender_is_disabled_and_leaves_at_next_rotation() {
+		new_test_ext().execute_with(|| {
+			let offender = author_account(1);
+			let details = OffenceDetails { offender: (offender.clone(), ()), reporters: vec![] };
+			assert!(DisableOffenders::on_offence(&[details], &[Perbill::from_percent(100)], 0).is_ok());
+
+			assert_eq!(Session::disabled_validators(), vec![0]);
+			assert_eq!(ValidatorSet::validators(), vec![author_account(2)]);
+			assert!(Session::validators().contains(&offender));
+
+			// The first rotation queues the new set, the second one enacts it.
+			Session::rotate_session();
+			Session::rotate_session();
+			assert_eq!(Session::validators(), vec![author_account(2)]);
+			assert!(Session::disabled_validators().is_empty());
+		});
+	}
+
+	#[test]
+	fn author_receives_share_of_fees_and_tips() {
+		new_test_ext().execute_with(|| {
+			initialize_block_by_authority(0);
+			let fee = Balances::issue(10_000);
+			let tip = Balances::issue(5_000);
+			DealWithFees::on_unbalanceds(vec![fee, tip].into_iter());
+
+			let author_share = AuthorFeeShare::get() * 15_000u128;
+			assert_eq!(Balances::free_balance(author_account(1)), author_share);
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 15_000 - author_share);
+		});
+	}
+
+	fn max_normal_weight() -> Weight {
+		let weights = BlockWeights::get();
+		weights.get(DispatchClass::Normal).max_total.unwrap_or(weights.max_block)
+	}
+
+	/// Runs `blocks` blocks of `weight` normal weight each, starting from `multiplier` and calling
+	/// `each` with the multiplier before and after every block.
+	fn run_blocks(
+		mut multiplier: Multiplier,
+		blocks: u32,
+		weight: Weight,
+		mut each: impl FnMut(Multiplier, Multiplier),
+	) -> Multiplier {
+		let mut t: sp_io::TestExternalities =
+			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap().into();
+		t.execute_with(|| {
+			System::set_block_consumed_resources(weight, 0);
+			for _ in 0..blocks {
+				let next = FeeMultiplierUpdate::convert(multiplier);
+				each(multiplier, next);
+				multiplier = next;
+			}
+			multiplier
+		})
+	}
+
+	fn fee_with_multiplier(multiplier: Multiplier, info: &DispatchInfo) -> Balance {
+		let mut t: sp_io::TestExternalities =
+			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap().into();
+		t.execute_with(|| {
+			pallet_transaction_payment::NextFeeMultiplier::put(multiplier);
+			TransactionPayment::compute_fee(0, info, 0)
+		})
+	}
+
+	#[test]
+	fn create_claim_costs_calibrated_fee() {
+		let info = Call::PoeModule(pallet_poe::Call::create_claim(vec![])).get_dispatch_info();
+		let fee = fee_with_multiplier(Multiplier::one(), &info);
+		assert!(fee.max(CREATE_CLAIM_FEE) - fee.min(CREATE_CLAIM_FEE) < 10, "fee was {}", fee);
+	}
+
+	#[test]
+	fn sustained_full_blocks_raise_fees() {
+		let end = run_blocks(Multiplier::one(), DAYS, max_normal_weight(), |before, after| assert!(after > before));
+		assert!(end > Multiplier::saturating_from_rational(13, 10));
+
+		let max_extrinsic = BlockWeights::get().get(DispatchClass::Normal).max_extrinsic.unwrap();
+		let heavy = DispatchInfo { weight: max_extrinsic, ..Default::default() };
+		assert!(fee_with_multiplier(end, &heavy) > fee_with_multiplier(Multiplier::one(), &heavy) * 13 / 10);
+
+		let claim = Call::PoeModule(pallet_poe::Call::create_claim(vec![])).get_dispatch_info();
+		assert!(fee_with_multiplier(end, &claim) > fee_with_multiplier(Multiplier::one(), &claim));
+	}
+
+	#[test]
+	fn target_fullness_keeps_fees_stable() {
+		let target = TargetBlockFullness::get() * max_normal_weight();
+		let end = run_blocks(Multiplier::one(), DAYS, target, |before, after| assert_eq!(after, before));
+		assert_eq!(end, Multiplier::one());
+	}
+
+	#[test]
+	fn empty_blocks_lower_fees_down_to_minimum() {
+		let end = run_blocks(Multiplier::one(), DAYS, 0, |before, after| assert!(after < before));
+		assert!(end < Multiplier::saturating_from_rational(9, 10));
+
+		let floor = run_blocks(MinimumMultiplier::get(), 10, 0, |_, after| {
+			assert_eq!(after, MinimumMultiplier::get());
+		});
+		assert_eq!(floor, MinimumMultiplier::get());
+	}
+
+	#[test]
+	fn proxy_types_filter_calls() {
+		let remark = Call::System(frame_system::Call::remark(vec![]));
+		let transfer = Call::Balances(pallet_balances::Call::transfer(author_account(2), 1));
+		let create_claim = Call::PoeModule(pallet_poe::Call::create_claim(vec![1]));
+		let transfer_claim = Call::PoeModule(pallet_poe::Call::transfer_claim(author_account(2), vec![1]));
+		let vested_transfer = Call::Vesting(pallet_vesting::Call::vested_transfer(
+			author_account(2),
+			pallet_vesting::VestingInfo { locked: 10, per_block: 1, starting_block: 0 },
+		));
+		let asset_transfer = Call::Assets(pallet_assets::Call::transfer(0, author_account(2), 1));
+		let list_claim = Call::PoeModule(pallet_poe::Call::list_claim(vec![1], 1));
+		let buy_claim = Call::PoeModule(pallet_poe::Call::buy_claim(vec![1], 1));
+		let schedule_revoke = Call::PoeModule(pallet_poe::Call::schedule_action(
+			vec![1], pallet_poe::ScheduledAction::Revoke, 10,
+		));
+		let schedule_transfer = Call::PoeModule(pallet_poe::Call::schedule_action(
+			vec![1], pallet_poe::ScheduledAction::Transfer(author_account(2)), 10,
+		));
+		let scheduled_transfer = Call::Scheduler(pallet_scheduler::Call::schedule(
+			10, None, 0, Box::new(transfer.clone()),
+		));
+		let batch = |calls: Vec<Call>| Call::Utility(pallet_utility::Call::batch(calls));
+		let derivative = |call: Call| Call::Utility(pallet_utility::Call::as_derivative(0, Box::new(call)));
+
+		assert!(ProxyType::Any.filter(&transfer));
+		assert!(ProxyType::PoeOnly.filter(&create_claim));
+		assert!(ProxyType::PoeOnly.filter(&transfer_claim));
+		assert!(!ProxyType::PoeOnly.filter(&remark));
+		assert!(!ProxyType::PoeOnly.filter(&buy_claim));
+		assert!(!ProxyType::PoeOnly.filter(&list_claim));
+		assert!(!ProxyType::PoeOnly.filter(&schedule_transfer));
+		assert!(!ProxyType::PoeOnly.filter(&batch(vec![create_claim.clone(), buy_claim.clone()])));
+		assert!(ProxyType::NonTransfer.filter(&remark));
+		assert!(ProxyType::NonTransfer.filter(&create_claim));
+		assert!(!ProxyType::NonTransfer.filter(&transfer));
+		assert!(!ProxyType::NonTransfer.filter(&transfer_claim));
+		assert!(!ProxyType::NonTransfer.filter(&vested_transfer));
+		assert!(!ProxyType::NonTransfer.filter(&asset_transfer));
+		assert!(!ProxyType::NonTransfer.filter(&list_claim));
+		assert!(!ProxyType::NonTransfer.filter(&buy_claim));
+		assert!(ProxyType::NonTransfer.filter(&schedule_revoke));
+		assert!(!ProxyType::NonTransfer.filter(&schedule_transfer));
+		assert!(!ProxyType::NonTransfer.filter(&scheduled_transfer));
+		assert!(ProxyType::NonTransfer.filter(&batch(vec![remark.clone(), create_claim.clone()])));
+		assert!(!ProxyType::NonTransfer.filter(&batch(vec![remark.clone(), transfer.clone()])));
+		assert!(!ProxyType::NonTransfer.filter(&derivative(batch(vec![asset_transfer.clone()]))));
+		assert!(ProxyType::PoeOnly.filter(&batch(vec![create_claim.clone(), transfer_claim.clone()])));
+		assert!(!ProxyType::PoeOnly.filter(&batch(vec![create_claim.clone(), remark.clone()])));
+		assert!(!ProxyType::PoeOnly.filter(&derivative(transfer.clone())));
+		assert!(ProxyType::Any.filter(&batch(vec![transfer.clone()])));
+
+		assert!(ProxyType::Any.is_superset(&ProxyType::PoeOnly));
+		assert!(!ProxyType::NonTransfer.is_superset(&ProxyType::PoeOnly));
+		assert!(!ProxyType::PoeOnly.is_superset(&ProxyType::Any));
+	}
+
+	#[test]
+	fn poe_only_proxy_cannot_buy_claims() {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> { balances: vec![(author_account(1), 1_000 * UNITS)] }
+			.assimilate_storage(&mut t).unwrap();
+		pallet_poe::GenesisConfig::<Runtime> { claims: vec![(vec![1], author_account(2))] }
+			.assimilate_storage(&mut t).unwrap();
+		sp_io::TestExternalities::from(t).execute_with(|| {
+			assert_ok!(Proxy::add_proxy(Origin::signed(author_account(1)), author_account(3), ProxyType::PoeOnly, 0));
+			assert_ok!(PoeModule::list_claim(Origin::signed(author_account(2)), vec![1], UNITS));
+			let balance = Balances::free_balance(author_account(1));
+
+			let via_proxy = |call| Proxy::proxy(
+				Origin::signed(author_account(3)), author_account(1), None, Box::new(call),
+			);
+			assert_ok!(via_proxy(Call::PoeModule(pallet_poe::Call::buy_claim(vec![1], UNITS))));
+			assert_eq!(PoeModule::claim(&[1]).map(|(owner, _)| owner), Some(author_account(2)));
+			assert_eq!(Balances::free_balance(author_account(1)), balance);
+
+			assert_ok!(via_proxy(Call::PoeModule(pallet_poe::Call::create_claim(vec![2]))));
+			assert_eq!(PoeModule::claim(&[2]).map(|(owner, _)| owner), Some(author_account(1)));
+		});
+	}
+
+	#[test]
+	fn base_filter_rejects_sudo_after_hand_over() {
+		new_test_ext().execute_with(|| {
+			let sudo = Call::Sudo(pallet_sudo::Call::sudo(Box::new(
+				Call::System(frame_system::Call::remark(vec![])),
+			)));
+			assert!(BaseFilter::filter(&sudo));
+
+			assert_ok!(SudoHandover::hand_over(Origin::root()));
+			assert!(!BaseFilter::filter(&sudo));
+			assert!(BaseFilter::filter(&Call::System(frame_system::Call::remark(vec![]))));
+		});
+	}
+
+	#[test]
+	fn base_filter_rejects_paused_calls() {
+		new_test_ext().execute_with(|| {
+			let create_claim = Call::PoeModule(pallet_poe::Call::create_claim(vec![1]));
+			let revoke_claim = Call::PoeModule(pallet_poe::Call::revoke_claim(vec![1]));
+			assert!(BaseFilter::filter(&create_claim));
+
+			assert_ok!(TransactionPause::pause_call(Origin::root(), b"PoeModule".to_vec(), b"create_claim".to_vec()));
+			assert!(!BaseFilter::filter(&create_claim));
+			assert!(BaseFilter::filter(&revoke_claim));
+
+			assert_ok!(TransactionPause::unpause_call(Origin::root(), b"PoeModule".to_vec(), b"create_claim".to_vec()));
+			assert!(BaseFilter::filter(&create_claim));
+		});
+	}
+
+	#[test]
+	fn governance_calls_cannot_be_paused() {
+		new_test_ext().execute_with(|| {
+			let remark = Call::System(frame_system::Call::remark(vec![]));
+			let set_members = Call::Council(pallet_collective::Call::set_members(vec![], None, 0));
+			let second = Call::Democracy(pallet_democracy::Call::second(0, 0));
+			let unpause = Call::TransactionPause(pallet_transaction_pause::Call::unpause_pallet(b"PoeModule".to_vec()));
+			let create_claim = Call::PoeModule(pallet_poe::Call::create_claim(vec![1]));
+
+			for pallet in &["System", "Council", "Democracy", "TransactionPause", "PoeModule"] {
+				assert_ok!(TransactionPause::pause_pallet(Origin::root(), pallet.as_bytes().to_vec()));
+			}
+			assert!(!BaseFilter::filter(&create_claim));
+			assert!(BaseFilter::filter(&remark));
+			assert!(BaseFilter::filter(&set_members));
+			assert!(BaseFilter::filter(&second));
+			assert!(BaseFilter::filter(&unpause));
+		});
+	}
+
+	#[test]
+	fn council_can_pause_and_manage_validators() {
+		let council = |yes, total| Origin::from(
+			pallet_collective::RawOrigin::<AccountId, CouncilCollective>::Members(yes, total),
+		);
+		type PauseOrigin = <Runtime as pallet_transaction_pause::Config>::PauseOrigin;
+		type AddRemoveOrigin = <Runtime as pallet_validator_set::Config>::AddRemoveOrigin;
+
+		assert!(PauseOrigin::ensure_origin(Origin::root()).is_ok());
+		assert!(PauseOrigin::ensure_origin(council(2, 3)).is_ok());
+		assert!(PauseOrigin::ensure_origin(council(1, 3)).is_err());
+		assert!(PauseOrigin::ensure_origin(Origin::signed(author_account(1))).is_err());
+		assert!(AddRemoveOrigin::ensure_origin(council(2, 3)).is_ok());
+		assert!(AddRemoveOrigin::ensure_origin(council(1, 2)).is_err());
+	}
+
+	fn claim_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_poe::GenesisConfig::<Runtime> { claims: vec![(vec![1], author_account(1))] }
+			.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	fn set_identity(who: &AccountId, judgements: Vec<(u32, IdentityJudgement)>) {
+		use pallet_identity::{Data, IdentityInfo, Registration};
+		pallet_identity::IdentityOf::<Runtime>::insert(who, Registration {
+			judgements,
+			deposit: 0,
+			info: IdentityInfo {
+				additional: vec![],
+				display: Data::Raw(b"alice".to_vec()),
+				legal: Data::None,
+				web: Data::None,
+				riot: Data::None,
+				email: Data::None,
+				pgp_fingerprint: None,
+				image: Data::None,
+				twitter: Data::None,
+			},
+		});
+	}
+
+	#[test]
+	fn claim_details_without_identity() {
+		claim_test_ext().execute_with(|| {
+			let details = claim_details(&[1]).unwrap();
+			assert_eq!(details.owner, author_account(1));
+			assert_eq!(details.block_number, 0);
+			assert_eq!(details.display, None);
+			assert!(details.judgements.is_empty());
+			assert_eq!(claim_details(&[2]), None);
+		});
+	}
+
+	#[test]
+	fn claim_details_hide_unjudged_identity() {
+		claim_test_ext().execute_with(|| {
+			set_identity(&author_account(1), vec![(0, IdentityJudgement::FeePaid(1))]);
+
+			let details = claim_details(&[1]).unwrap();
+			assert_eq!(details.display, None);
+			assert_eq!(details.judgements, vec![(0, IdentityJudgement::FeePaid(1))]);
+		});
+	}
+
+	#[test]
+	fn claim_details_show_judged_identity() {
+		claim_test_ext().execute_with(|| {
+			set_identity(&author_account(1), vec![
+				(0, IdentityJudgement::OutOfDate),
+				(1, IdentityJudgement::KnownGood),
+			]);
+
+			let details = claim_details(&[1]).unwrap();
+			assert_eq!(details.display, Some(b"alice".to_vec()));
+			assert_eq!(details.judgements.len(), 2);
+		});
+	}
+
+	/// Calls the chain extension function named by the first input byte with the rest of the
+	/// input and returns its output.
+	const POE_CONTRACT: &str = r#"
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) input buffer length
+	(data (i32.const 0) "\00\02")
+	;; [4, 516) input buffer
+	;; [516, 520) output buffer length
+	(data (i32.const 516) "\00\02")
+	;; [520, 1032) output buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+		(if (i32.eqz (call $seal_call_chain_extension
+			(i32.load8_u (i32.const 4))
+			(i32.const 5)
+			(i32.sub (i32.load (i32.const 0)) (i32.const 1))
+			(i32.const 520)
+			(i32.const 516)
+		))
+			(then)
+			(else (unreachable))
+		)
+		(call $seal_return (i32.const 0) (i32.const 520) (i32.load (i32.const 516)))
+	)
+)
+"#;
+
+	fn contracts_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> { balances: vec![(author_account(1), 1_000 * UNITS)] }
+			.assimilate_storage(&mut t).unwrap();
+		pallet_contracts::GenesisConfig::<Runtime> { current_schedule: Default::default() }
+			.assimilate_storage(&mut t).unwrap();
+		pallet_poe::GenesisConfig::<Runtime> { claims: vec![(vec![1], author_account(2))] }
+			.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	/// Deploys `POE_CONTRACT` from `author_account(1)` and returns its address.
+	fn deploy_poe_contract() -> AccountId {
+		let code = wat::parse_str(POE_CONTRACT).unwrap();
+		let code_hash = <Runtime as frame_system::Config>::Hashing::hash(&code);
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(author_account(1)),
+			100 * UNITS,
+			BlockWeights::get().max_block,
+			code,
+			vec![],
+			vec![],
+		));
+		Contracts::contract_address(&author_account(1), &code_hash, &[])
+	}
+
+	fn call_poe_contract(contract: &AccountId, func_id: u8, proof: &[u8]) -> Result<Vec<u8>, DispatchError> {
+		let input = [&[func_id][..], proof].concat();
+		Contracts::bare_call(author_account(1), contract.clone(), 0, BlockWeights::get().max_block, input)
+			.exec_result
+			.map(|v| v.data.0)
+	}
+
+	#[test]
+	fn contracts_read_claims() {
+		contracts_test_ext().execute_with(|| {
+			let contract = deploy_poe_contract();
+
+			assert_eq!(
+				call_poe_contract(&contract, 1, &[1]),
+				Ok(Some((author_account(2), 0 as BlockNumber)).encode()),
+			);
+			assert_eq!(call_poe_contract(&contract, 1, &[2]), Ok(None::<(AccountId, BlockNumber)>.encode()));
+		});
+	}
+
+	#[test]
+	fn contracts_create_claims() {
+		contracts_test_ext().execute_with(|| {
+			let contract = deploy_poe_contract();
+
+			assert!(call_poe_contract(&contract, 2, &[2]).is_ok());
+			assert_eq!(PoeModule::claim(&[2]).map(|(owner, _)| owner), Some(contract.clone()));
+			assert!(call_poe_contract(&contract, 2, &[1]).is_err());
+			assert_eq!(PoeModule::claim(&[1]).map(|(owner, _)| owner), Some(author_account(2)));
+			assert!(call_poe_contract(&contract, 3, &[3]).is_err());
+		});
+	}
+
+	#[test]
+	fn contracts_cannot_create_paused_claims() {
+		contracts_test_ext().execute_with(|| {
+			let contract = deploy_poe_contract();
+			assert_ok!(TransactionPause::pause_call(
+				Origin::root(), b"PoeModule".to_vec(), b"create_claim".to_vec(),
+			));
+
+			assert!(call_poe_contract(&contract, 2, &[2]).is_err());
+			assert_eq!(PoeModule::claim(&[2]), None);
+		});
+	}
+}
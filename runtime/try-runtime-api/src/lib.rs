@@ -0,0 +1,39 @@
+//! Runtime API for checking runtime upgrades against existing state.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Encode, Decode};
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// A check that failed around a runtime upgrade.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct UpgradeFailure {
+	/// The name of the pallet whose check failed.
+	pub pallet: Vec<u8>,
+	/// Whether the check ran before the upgrade, rather than after it.
+	pub pre_upgrade: bool,
+	/// What the check found.
+	pub message: Vec<u8>,
+}
+
+/// The outcome of running the runtime upgrade hooks.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct UpgradeReport {
+	/// The weight reported by `on_runtime_upgrade` of all pallets.
+	pub weight: u64,
+	/// The weight a block may use at most.
+	pub max_block_weight: u64,
+	/// The checks that failed, in the order they ran.
+	pub failures: Vec<UpgradeFailure>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Runs this runtime's upgrade on the state it is called at. Only meant for offline checks;
+	/// the state changes must not be committed.
+	pub trait TryRuntime {
+		/// Run every pallet's `pre_upgrade` check, then `on_runtime_upgrade`, then every
+		/// `post_upgrade` check.
+		fn on_runtime_upgrade() -> UpgradeReport;
+	}
+}